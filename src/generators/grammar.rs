@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+use tree_sitter::Node;
+
+use super::Budget;
+use crate::mutators::{find_node, parse};
+
+// seed 없이 새 파일을 만드는 generator.
+// tree-sitter-rust의 node-types.json 에서 node 종류마다 field와 자식으로 올 수 있는 종류를 읽고,
+// 빈 source_file 에서 시작해서 item을 하나씩 만든다.
+// node 하나는 donor(모든 파일에서 모은 같은 종류의 코드) 하나를 틀로 고른 다음,
+// 그 자식들을 grammar가 허락하는 다른 종류의 node로 다시 만들어 바꿔 끼운다.
+// depth를 넘어가면 더 들어가지 않고 donor를 그대로 쓴다.
+
+// 자식을 다시 만들 확률
+const REGENERATE_PROBABILITY: f64 = 0.5;
+
+// depth를 넘어갔을 때 donor 중 몇 개를 뽑아 가장 짧은 것을 쓸지
+const LEAF_SAMPLES: usize = 4;
+
+// grammar는 source_file 아래에 둘 수 있다고 하지만 rustc는 받지 않는 것들
+const NOT_ITEMS: [&str; 8] = [
+    "expression_statement",
+    "shebang",
+    "let_declaration",
+    "associated_type",
+    "function_signature_item",
+    "empty_statement",
+    "attribute_item",
+    "inner_attribute_item",
+];
+
+// donor 조각을 parse할 때 앞뒤에 붙여보는 문맥들. {} 자리에 조각이 들어간다
+const CONTEXTS: [&str; 12] = [
+    "{}",
+    "fn __f() { {} }",
+    "fn __f() { {}; }",
+    "fn __f() { let _ = {}; }",
+    "fn __f() { let {} = (); }",
+    "fn __f() { match () { {} } }",
+    "fn __f({}) {}",
+    "type __T = {};",
+    "impl __S { {} }",
+    "struct __S { {} }",
+    "enum __E { {} }",
+    "fn __f<{}>() {}",
+];
+
+// node-types.json 에서 쓰는 부분만 읽는다
+#[derive(Deserialize)]
+struct NodeType {
+    #[serde(rename = "type")]
+    kind: String,
+    // _expression 같은 supertype에만 있다
+    subtypes: Option<Vec<TypeRef>>,
+    #[serde(default)]
+    fields: BTreeMap<String, ChildTypes>,
+    children: Option<ChildTypes>,
+}
+
+#[derive(Deserialize)]
+struct ChildTypes {
+    types: Vec<TypeRef>,
+}
+
+#[derive(Deserialize)]
+struct TypeRef {
+    #[serde(rename = "type")]
+    kind: String,
+    named: bool,
+}
+
+fn parse_node_types(json: &str) -> serde_json::Result<Vec<NodeType>> {
+    serde_json::from_str(json)
+}
+
+// field 하나, 또는 이름 없는 자식들에 올 수 있는 node 종류
+struct Slot {
+    // None이면 이름 없는 자식
+    field: Option<String>,
+    types: Vec<String>,
+}
+
+pub struct Grammar {
+    // _expression 같은 supertype -> 그 아래 종류들
+    subtypes: HashMap<String, Vec<String>>,
+    slots: HashMap<String, Vec<Slot>>,
+}
+
+fn named_types(types: &[TypeRef]) -> Vec<String> {
+    types
+        .iter()
+        .filter(|t| t.named)
+        .map(|t| t.kind.clone())
+        .collect()
+}
+
+impl Grammar {
+    pub fn new() -> Grammar {
+        let node_types =
+            parse_node_types(tree_sitter_rust::NODE_TYPES).expect("Invalid node types.");
+        let mut grammar = Grammar {
+            subtypes: HashMap::new(),
+            slots: HashMap::new(),
+        };
+        for node_type in node_types {
+            if let Some(subtypes) = node_type.subtypes {
+                grammar
+                    .subtypes
+                    .insert(node_type.kind, named_types(&subtypes));
+                continue;
+            }
+            let mut slots = vec![];
+            for (field, info) in node_type.fields {
+                slots.push(Slot {
+                    field: Some(field),
+                    types: named_types(&info.types),
+                });
+            }
+            if let Some(children) = node_type.children {
+                slots.push(Slot {
+                    field: None,
+                    types: named_types(&children.types),
+                });
+            }
+            grammar.slots.insert(node_type.kind, slots);
+        }
+        grammar
+    }
+
+    // supertype이면 실제 node 종류들로 펼친다
+    fn concrete(&self, kind: &str) -> Vec<String> {
+        match self.subtypes.get(kind) {
+            Some(subtypes) => subtypes.iter().flat_map(|s| self.concrete(s)).collect(),
+            None => vec![kind.to_string()],
+        }
+    }
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// donor 조각을 문맥에 넣어 parse하고, 조각과 정확히 겹치는 kind node가 있는 문맥을 찾는다
+fn parse_fragment(kind: &str, fragment: &str) -> Option<(String, usize, tree_sitter::Tree)> {
+    for context in CONTEXTS {
+        let (open, close) = context.split_once("{}").unwrap();
+        let code = format!("{}{}{}", open, fragment, close);
+        let tree = parse(&code);
+        let start = open.len();
+        let end = start + fragment.len();
+        let info = (kind, start, end, Default::default(), Default::default());
+        if find_node(&tree, &info).is_some() {
+            return Some((code, start, tree));
+        }
+    }
+    None
+}
+
+struct Generator<'a> {
+    grammar: &'a Grammar,
+    donors: &'a HashMap<&'a str, Vec<String>>,
+    budget: &'a Budget,
+}
+
+impl Generator<'_> {
+    fn donors_of(&self, kind: &str) -> Vec<&String> {
+        self.donors
+            .get(kind)
+            .map(|d| d.iter().filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    // kind(supertype이어도 된다) 하나를 새로 만든다. 만들 수 없으면 None
+    fn generate(&self, kind: &str, depth: usize) -> Option<String> {
+        let mut rng = rand::thread_rng();
+        let kinds: Vec<String> = self
+            .grammar
+            .concrete(kind)
+            .into_iter()
+            .filter(|k| !self.donors_of(k).is_empty())
+            .collect();
+        let kind = kinds.choose(&mut rng)?;
+        let donors = self.donors_of(kind);
+        if depth >= self.budget.max_depth {
+            return donors
+                .choose_multiple(&mut rng, LEAF_SAMPLES)
+                .min_by_key(|d| d.len())
+                .map(|d| d.to_string());
+        }
+        let donor = donors.choose(&mut rng)?;
+        let Some((code, start, tree)) = parse_fragment(kind, donor) else {
+            return Some(donor.to_string());
+        };
+        let info = (
+            kind.as_str(),
+            start,
+            start + donor.len(),
+            Default::default(),
+            Default::default(),
+        );
+        let node = find_node(&tree, &info)?;
+        Some(self.regenerate_children(&code, node, depth))
+    }
+
+    // node의 자식들 중 일부를 grammar가 허락하는 다른 종류로 새로 만들어 바꾼 node 코드
+    fn regenerate_children(&self, code: &str, node: Node, depth: usize) -> String {
+        let mut rng = rand::thread_rng();
+        let mut replacements: Vec<(usize, usize, String)> = vec![];
+        // 이름 있는 자식마다 어느 field에 들어있는지
+        let mut children: Vec<(Option<&str>, Node)> = vec![];
+        let mut cursor = node.walk();
+        if cursor.goto_first_child() {
+            loop {
+                let child = cursor.node();
+                if child.is_named() && !child.kind().ends_with("comment") {
+                    children.push((cursor.field_name(), child));
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+        if let Some(slots) = self.grammar.slots.get(node.kind()) {
+            for slot in slots.iter().filter(|s| !s.types.is_empty()) {
+                for (_, child) in children.iter().filter(|(f, _)| *f == slot.field.as_deref()) {
+                    if !rng.gen_bool(REGENERATE_PROBABILITY) {
+                        continue;
+                    }
+                    let kind = slot.types.choose(&mut rng).unwrap();
+                    if let Some(new) = self.generate(kind, depth + 1) {
+                        replacements.push((child.start_byte(), child.end_byte(), new));
+                    }
+                }
+            }
+        }
+        replacements.sort_by_key(|r| r.0);
+        let mut out = String::new();
+        let mut last = node.start_byte();
+        for (start, end, new) in replacements {
+            if start < last {
+                continue;
+            }
+            out.push_str(&code[last..start]);
+            out.push_str(&new);
+            last = end;
+        }
+        out.push_str(&code[last..node.end_byte()]);
+        out
+    }
+}
+
+// 빈 source_file 에서 시작해 item을 max_items 개 만든 파일 하나.
+pub fn generate_file(
+    grammar: &Grammar,
+    donors: &HashMap<&str, Vec<String>>,
+    budget: &Budget,
+) -> String {
+    let generator = Generator {
+        grammar,
+        donors,
+        budget,
+    };
+    let top_level: Vec<String> = grammar
+        .slots
+        .get("source_file")
+        .map(|slots| {
+            slots
+                .iter()
+                .flat_map(|s| s.types.iter().flat_map(|t| grammar.concrete(t)))
+                .filter(|t| !NOT_ITEMS.contains(&t.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut items = vec![];
+    // 만들 수 없는 종류를 고를 수 있으니 몇 번 더 시도한다
+    for _ in 0..budget.max_items * 4 {
+        if items.len() >= budget.max_items {
+            break;
+        }
+        let Some(kind) = top_level.choose(&mut rand::thread_rng()) else {
+            break;
+        };
+        if let Some(item) = generator.generate(kind, 0) {
+            items.push(item);
+        }
+    }
+    let mut source_code = items.join("\n\n");
+    source_code.push('\n');
+    source_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_types_parse() {
+        let node_types = parse_node_types(tree_sitter_rust::NODE_TYPES).unwrap();
+        assert!(node_types.iter().any(|n| n.kind == "function_item"));
+        assert!(parse_node_types("[{\"type\": ").is_err());
+    }
+
+    #[test]
+    fn grammar_has_fields_and_subtypes() {
+        let grammar = Grammar::new();
+        let function = &grammar.slots["function_item"];
+        let name = function
+            .iter()
+            .find(|s| s.field.as_deref() == Some("name"))
+            .unwrap();
+        assert!(name.types.contains(&"identifier".to_string()));
+        let expressions = grammar.concrete("_expression");
+        assert!(expressions.contains(&"binary_expression".to_string()));
+        assert!(!expressions.iter().any(|k| k.starts_with('_')));
+    }
+}
@@ -0,0 +1,10 @@
+pub mod grammar;
+pub mod typed;
+
+// 새 파일을 만드는 generator들이 같이 쓰는 크기 제한
+pub struct Budget {
+    // grammar: node를 다시 만들며 들어가는 최대 깊이, typed: expression과 block의 최대 깊이
+    pub max_depth: usize,
+    // grammar: source_file에 넣을 item 수, typed: main 말고 만들 함수 수
+    pub max_items: usize,
+}
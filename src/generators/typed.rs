@@ -0,0 +1,720 @@
+use rand::rngs::ThreadRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::Budget;
+
+// type을 추적하면서 항상 컴파일되는 프로그램을 만드는 generator. (Csmith, YARPGen 같은 방식)
+// splice로 만든 코드는 거의 다 type check에서 막혀서 MIR와 codegen까지 못 간다.
+// 여기서는 변수, 함수, struct, enum, trait 환경을 들고 다니면서 type이 맞는 expression만 만든다.
+// 모든 type은 Copy이고, 산술은 wrapping_*/checked_* 만 써서 panic, overflow, UB가 없다.
+// main은 자기 변수들의 checksum을 출력하므로 opt-level을 바꿔 컴파일한 결과와 비교할 수 있다.
+
+const INTS: [&str; 10] = [
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+];
+
+// 정수 type이 받는 메서드 중 같은 type 두 개를 받아 같은 type을 돌려주는 것들
+const INT_METHODS: [&str; 3] = ["wrapping_add", "wrapping_sub", "wrapping_mul"];
+
+const INT_OPERATORS: [&str; 3] = ["^", "&", "|"];
+
+const COMPARISONS: [&str; 6] = ["<", "<=", ">", ">=", "==", "!="];
+
+// 프로그램 맨 앞에 항상 들어가는 trait과 impl. 안 쓰는 변수, 함수가 많아서 warning은 끈다
+const PRELUDE: &str = "#![allow(warnings)]
+
+trait Checksum {
+    fn checksum(&self) -> u64;
+}
+
+impl Checksum for bool {
+    fn checksum(&self) -> u64 {
+        *self as u64
+    }
+}
+
+impl<A: Checksum, B: Checksum> Checksum for (A, B) {
+    fn checksum(&self) -> u64 {
+        self.0.checksum().wrapping_mul(31).wrapping_add(self.1.checksum())
+    }
+}
+
+impl<A: Checksum, B: Checksum, C: Checksum> Checksum for (A, B, C) {
+    fn checksum(&self) -> u64 {
+        let ab = self.0.checksum().wrapping_mul(31).wrapping_add(self.1.checksum());
+        ab.wrapping_mul(31).wrapping_add(self.2.checksum())
+    }
+}
+
+impl<T: Checksum, const N: usize> Checksum for [T; N] {
+    fn checksum(&self) -> u64 {
+        self.iter().fold(0u64, |a, x| a.wrapping_mul(31).wrapping_add(x.checksum()))
+    }
+}
+
+trait Mix {
+    fn mix(&self, x: u64) -> u64;
+}
+
+fn mix_twice<T: Mix>(t: &T, x: u64) -> u64 {
+    t.mix(t.mix(x))
+}
+";
+
+#[derive(Clone, PartialEq)]
+enum Ty {
+    Int(&'static str),
+    Bool,
+    Tuple(Vec<Ty>),
+    Array(Box<Ty>, usize),
+    // structs, enums 의 index
+    Struct(usize),
+    Enum(usize),
+}
+
+struct StructDef {
+    name: String,
+    fields: Vec<Ty>,
+}
+
+struct EnumDef {
+    name: String,
+    // V0, V1, .. 의 payload
+    variants: Vec<Option<Ty>>,
+}
+
+struct FnDef {
+    name: String,
+    params: Vec<Ty>,
+    ret: Ty,
+}
+
+struct Var {
+    name: String,
+    ty: Ty,
+    mutable: bool,
+}
+
+struct Program<'a> {
+    budget: &'a Budget,
+    rng: ThreadRng,
+    structs: Vec<StructDef>,
+    enums: Vec<EnumDef>,
+    fns: Vec<FnDef>,
+    // 안쪽 block일수록 뒤에 있다
+    scopes: Vec<Vec<Var>>,
+    next_name: usize,
+}
+
+impl Program<'_> {
+    fn fresh(&mut self, prefix: &str) -> String {
+        self.next_name += 1;
+        format!("{}{}", prefix, self.next_name)
+    }
+
+    fn type_name(&self, ty: &Ty) -> String {
+        match ty {
+            Ty::Int(int) => int.to_string(),
+            Ty::Bool => "bool".to_string(),
+            Ty::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(|e| self.type_name(e))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Ty::Array(element, len) => format!("[{}; {}]", self.type_name(element), len),
+            Ty::Struct(i) => self.structs[*i].name.clone(),
+            Ty::Enum(i) => self.enums[*i].name.clone(),
+        }
+    }
+
+    fn random_int(&mut self) -> Ty {
+        Ty::Int(INTS.choose(&mut self.rng).unwrap())
+    }
+
+    // depth가 클수록 단순한 type
+    fn random_type(&mut self, depth: usize) -> Ty {
+        let choice = if depth >= 2 {
+            self.rng.gen_range(0..3)
+        } else {
+            self.rng.gen_range(0..7)
+        };
+        match choice {
+            0 | 1 => self.random_int(),
+            2 => Ty::Bool,
+            3 => {
+                let len = self.rng.gen_range(2..=3);
+                Ty::Tuple((0..len).map(|_| self.random_type(depth + 1)).collect())
+            }
+            4 => {
+                let len = self.rng.gen_range(1..=4);
+                Ty::Array(Box::new(self.random_type(depth + 1)), len)
+            }
+            5 if !self.structs.is_empty() => Ty::Struct(self.rng.gen_range(0..self.structs.len())),
+            6 if !self.enums.is_empty() => Ty::Enum(self.rng.gen_range(0..self.enums.len())),
+            _ => self.random_int(),
+        }
+    }
+
+    fn int_literal(&mut self, int: &str) -> String {
+        let signed = int.starts_with('i');
+        let bits: u32 = match int {
+            "i8" | "u8" => 8,
+            "i16" | "u16" => 16,
+            "i32" | "u32" => 32,
+            _ => 64,
+        };
+        match self.rng.gen_range(0..6) {
+            0 => format!("0{}", int),
+            1 => format!("1{}", int),
+            2 => format!("{}::MAX", int),
+            3 => format!("{}::MIN", int),
+            _ if signed => {
+                // overflowing_literals 에 걸리지 않도록 MIN은 빼고 고른다
+                let max = (1i128 << (bits - 1)) - 1;
+                let value = self.rng.gen_range(-max..=max);
+                if value < 0 {
+                    format!("({}{})", value, int)
+                } else {
+                    format!("{}{}", value, int)
+                }
+            }
+            _ => {
+                let max = if bits == 64 {
+                    u64::MAX as u128
+                } else {
+                    (1u128 << bits) - 1
+                };
+                format!("{}{}", self.rng.gen_range(0..=max), int)
+            }
+        }
+    }
+
+    // ty 값을 읽을 수 있는 자리들: 변수, field, tuple 원소, 배열 원소
+    fn places(&mut self, ty: &Ty, mutable_only: bool) -> Vec<String> {
+        let mut roots = vec![];
+        for scope in self.scopes.iter() {
+            for var in scope.iter() {
+                if !mutable_only || var.mutable {
+                    roots.push((var.name.clone(), var.ty.clone()));
+                }
+            }
+        }
+        let mut out = vec![];
+        for (name, var_ty) in roots {
+            self.collect_places(&name, &var_ty, ty, 0, &mut out);
+        }
+        out
+    }
+
+    fn collect_places(
+        &mut self,
+        place: &str,
+        place_ty: &Ty,
+        ty: &Ty,
+        depth: usize,
+        out: &mut Vec<String>,
+    ) {
+        if place_ty == ty {
+            out.push(place.to_string());
+        }
+        if depth >= 2 {
+            return;
+        }
+        match place_ty {
+            Ty::Tuple(elements) => {
+                for (i, element) in elements.clone().iter().enumerate() {
+                    self.collect_places(&format!("{}.{}", place, i), element, ty, depth + 1, out);
+                }
+            }
+            Ty::Struct(s) => {
+                for (i, field) in self.structs[*s].fields.clone().iter().enumerate() {
+                    self.collect_places(&format!("{}.f{}", place, i), field, ty, depth + 1, out);
+                }
+            }
+            Ty::Array(element, len) => {
+                let index = self.rng.gen_range(0..*len);
+                self.collect_places(
+                    &format!("{}[{}]", place, index),
+                    element,
+                    ty,
+                    depth + 1,
+                    out,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    // 값을 직접 만드는 expression
+    fn construct(&mut self, ty: &Ty, depth: usize) -> String {
+        match ty {
+            Ty::Int(int) => self.int_literal(int),
+            Ty::Bool => if self.rng.gen_bool(0.5) {
+                "true"
+            } else {
+                "false"
+            }
+            .to_string(),
+            Ty::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(|e| self.expression(e, depth + 1))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Ty::Array(element, len) => format!(
+                "[{}]",
+                (0..*len)
+                    .map(|_| self.expression(element, depth + 1))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Ty::Struct(s) => {
+                let fields = self.structs[*s].fields.clone();
+                let values: Vec<String> = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| format!("f{}: {}", i, self.expression(f, depth + 1)))
+                    .collect();
+                format!("{} {{ {} }}", self.structs[*s].name, values.join(", "))
+            }
+            Ty::Enum(e) => {
+                let variants = self.enums[*e].variants.clone();
+                let v = self.rng.gen_range(0..variants.len());
+                match &variants[v] {
+                    Some(payload) => {
+                        let value = self.expression(payload, depth + 1);
+                        format!("{}::V{}({})", self.enums[*e].name, v, value)
+                    }
+                    None => format!("{}::V{}", self.enums[*e].name, v),
+                }
+            }
+        }
+    }
+
+    // ty 값을 돌려주는 함수 호출
+    fn call(&mut self, ty: &Ty, depth: usize) -> Option<String> {
+        let candidates: Vec<usize> = (0..self.fns.len())
+            .filter(|i| self.fns[*i].ret == *ty)
+            .collect();
+        let f = *candidates.choose(&mut self.rng)?;
+        let params = self.fns[f].params.clone();
+        let args: Vec<String> = params
+            .iter()
+            .map(|p| self.expression(p, depth + 1))
+            .collect();
+        Some(format!("{}({})", self.fns[f].name, args.join(", ")))
+    }
+
+    // enum 변수를 match해서 ty 값을 만든다
+    fn match_enum(&mut self, ty: &Ty, depth: usize) -> Option<String> {
+        let e = self.rng.gen_range(0..self.enums.len().max(1));
+        if self.enums.is_empty() {
+            return None;
+        }
+        let scrutinee = self
+            .places(&Ty::Enum(e), false)
+            .choose(&mut self.rng)?
+            .clone();
+        let variants = self.enums[e].variants.clone();
+        let mut arms = vec![];
+        for (v, payload) in variants.iter().enumerate() {
+            match payload {
+                Some(payload) => {
+                    let binding = self.fresh("x");
+                    self.scopes.push(vec![Var {
+                        name: binding.clone(),
+                        ty: payload.clone(),
+                        mutable: false,
+                    }]);
+                    let arm = self.expression(ty, depth + 1);
+                    self.scopes.pop();
+                    arms.push(format!(
+                        "{}::V{}({}) => {}",
+                        self.enums[e].name, v, binding, arm
+                    ));
+                }
+                None => {
+                    let arm = self.expression(ty, depth + 1);
+                    arms.push(format!("{}::V{} => {}", self.enums[e].name, v, arm));
+                }
+            }
+        }
+        Some(format!("match {} {{ {} }}", scrutinee, arms.join(", ")))
+    }
+
+    fn int_expression(&mut self, int: &'static str, depth: usize) -> Option<String> {
+        let ty = Ty::Int(int);
+        Some(match self.rng.gen_range(0..7) {
+            0 => format!(
+                "({}).{}({})",
+                self.expression(&ty, depth + 1),
+                INT_METHODS.choose(&mut self.rng).unwrap(),
+                self.expression(&ty, depth + 1)
+            ),
+            1 => format!(
+                "({} {} {})",
+                self.expression(&ty, depth + 1),
+                INT_OPERATORS.choose(&mut self.rng).unwrap(),
+                self.expression(&ty, depth + 1)
+            ),
+            2 => {
+                let amount = self.random_int();
+                format!(
+                    "({}).wrapping_shl({} as u32)",
+                    self.expression(&ty, depth + 1),
+                    self.expression(&amount, depth + 1)
+                )
+            }
+            3 => format!(
+                "({}).checked_div({}).unwrap_or({})",
+                self.expression(&ty, depth + 1),
+                self.expression(&ty, depth + 1),
+                self.int_literal(int)
+            ),
+            4 => {
+                let from = if self.rng.gen_bool(0.2) {
+                    Ty::Bool
+                } else {
+                    self.random_int()
+                };
+                format!("({} as {})", self.expression(&from, depth + 1), int)
+            }
+            5 if int == "u64" && !self.structs.is_empty() => {
+                let s = self.rng.gen_range(0..self.structs.len());
+                let target = self
+                    .places(&Ty::Struct(s), false)
+                    .choose(&mut self.rng)?
+                    .clone();
+                let x = self.expression(&ty, depth + 1);
+                if self.rng.gen_bool(0.5) {
+                    format!("{}.mix({})", target, x)
+                } else {
+                    format!("mix_twice(&{}, {})", target, x)
+                }
+            }
+            _ => format!(
+                "({}).rotate_left({})",
+                self.expression(&ty, depth + 1),
+                self.rng.gen_range(0..64)
+            ),
+        })
+    }
+
+    fn bool_expression(&mut self, depth: usize) -> String {
+        match self.rng.gen_range(0..3) {
+            0 => {
+                let int = self.random_int();
+                format!(
+                    "({} {} {})",
+                    self.expression(&int, depth + 1),
+                    COMPARISONS.choose(&mut self.rng).unwrap(),
+                    self.expression(&int, depth + 1)
+                )
+            }
+            1 => format!("!{}", self.expression(&Ty::Bool, depth + 1)),
+            _ => format!(
+                "({} {} {})",
+                self.expression(&Ty::Bool, depth + 1),
+                if self.rng.gen_bool(0.5) { "&&" } else { "||" },
+                self.expression(&Ty::Bool, depth + 1)
+            ),
+        }
+    }
+
+    // ty 값을 돌려주는 expression. 안 되는 방법을 고르면 다른 방법으로 넘어간다
+    fn expression(&mut self, ty: &Ty, depth: usize) -> String {
+        if depth >= self.budget.max_depth {
+            return match self.places(ty, false).choose(&mut self.rng) {
+                Some(place) if self.rng.gen_bool(0.7) => place.clone(),
+                _ => self.construct(ty, depth),
+            };
+        }
+        let expression = match self.rng.gen_range(0..8) {
+            0 | 1 => self.places(ty, false).choose(&mut self.rng).cloned(),
+            2 => Some(self.construct(ty, depth)),
+            3 => self.call(ty, depth),
+            4 => self.match_enum(ty, depth),
+            5 => Some(format!(
+                "if {} {{ {} }} else {{ {} }}",
+                self.expression(&Ty::Bool, depth + 1),
+                self.expression(ty, depth + 1),
+                self.expression(ty, depth + 1)
+            )),
+            _ => match ty {
+                Ty::Int(int) => self.int_expression(int, depth),
+                Ty::Bool => Some(self.bool_expression(depth)),
+                _ => None,
+            },
+        };
+        expression.unwrap_or_else(|| self.construct(ty, depth))
+    }
+
+    fn declare(&mut self, name: String, ty: Ty, mutable: bool) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .push(Var { name, ty, mutable });
+    }
+
+    fn block(&mut self, depth: usize, indent: usize) -> Vec<String> {
+        self.scopes.push(vec![]);
+        let count = self.rng.gen_range(1..=3);
+        let statements = (0..count).map(|_| self.statement(depth, indent)).collect();
+        self.scopes.pop();
+        statements
+    }
+
+    fn statement(&mut self, depth: usize, indent: usize) -> String {
+        let pad = "    ".repeat(indent);
+        let choice = if depth >= self.budget.max_depth {
+            0
+        } else {
+            self.rng.gen_range(0..6)
+        };
+        match choice {
+            1 | 2 => {
+                let ty = self.random_type(0);
+                if let Some(place) = self.places(&ty, true).choose(&mut self.rng).cloned() {
+                    let value = self.expression(&ty, depth + 1);
+                    return format!("{}{} = {};", pad, place, value);
+                }
+            }
+            3 => {
+                let condition = self.expression(&Ty::Bool, depth + 1);
+                let then = self.block(depth + 1, indent + 1);
+                let otherwise = self.block(depth + 1, indent + 1);
+                return format!(
+                    "{pad}if {} {{\n{}\n{pad}}} else {{\n{}\n{pad}}}",
+                    condition,
+                    then.join("\n"),
+                    otherwise.join("\n")
+                );
+            }
+            4 => {
+                let index = self.fresh("i");
+                let bound = self.rng.gen_range(0..8);
+                self.scopes.push(vec![Var {
+                    name: index.clone(),
+                    ty: Ty::Int("usize"),
+                    mutable: false,
+                }]);
+                let body = self.block(depth + 1, indent + 1);
+                self.scopes.pop();
+                return format!(
+                    "{pad}for {} in 0..{}usize {{\n{}\n{pad}}}",
+                    index,
+                    bound,
+                    body.join("\n")
+                );
+            }
+            _ => {}
+        }
+        let ty = self.random_type(0);
+        let name = self.fresh("v");
+        let value = self.expression(&ty, depth + 1);
+        let mutable = self.rng.gen_bool(0.5);
+        let statement = format!(
+            "{}let {}{}: {} = {};",
+            pad,
+            if mutable { "mut " } else { "" },
+            name,
+            self.type_name(&ty),
+            value
+        );
+        self.declare(name, ty, mutable);
+        statement
+    }
+
+    fn struct_item(&mut self) -> String {
+        let name = self.fresh("S");
+        let count = self.rng.gen_range(1..=3);
+        let fields: Vec<Ty> = (0..count).map(|_| self.random_type(1)).collect();
+        let declarations: Vec<String> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| format!("    f{}: {},", i, self.type_name(f)))
+            .collect();
+        let checksum: Vec<String> = (0..fields.len())
+            .map(|i| format!(".wrapping_mul(31).wrapping_add(self.f{}.checksum())", i))
+            .collect();
+        let salt: u64 = self.rng.gen();
+        let item = format!(
+            "#[derive(Clone, Copy)]\nstruct {name} {{\n{}\n}}\n\n\
+             impl Checksum for {name} {{\n    fn checksum(&self) -> u64 {{\n        0u64{}\n    }}\n}}\n\n\
+             impl Mix for {name} {{\n    fn mix(&self, x: u64) -> u64 {{\n        self.checksum().wrapping_mul(x | 1) ^ {}\n    }}\n}}",
+            declarations.join("\n"),
+            checksum.join(""),
+            salt
+        );
+        self.structs.push(StructDef { name, fields });
+        item
+    }
+
+    fn enum_item(&mut self) -> String {
+        let name = self.fresh("E");
+        let count = self.rng.gen_range(1..=3);
+        let variants: Vec<Option<Ty>> = (0..count)
+            .map(|_| {
+                if self.rng.gen_bool(0.6) {
+                    Some(self.random_type(1))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let declarations: Vec<String> = variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match v {
+                Some(payload) => format!("    V{}({}),", i, self.type_name(payload)),
+                None => format!("    V{},", i),
+            })
+            .collect();
+        let arms: Vec<String> = variants
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match v {
+                Some(_) => format!(
+                    "            {name}::V{i}(x) => {i}u64.wrapping_mul(31).wrapping_add(x.checksum()),"
+                ),
+                None => format!("            {name}::V{i} => {i}u64,"),
+            })
+            .collect();
+        let item = format!(
+            "#[derive(Clone, Copy)]\nenum {name} {{\n{}\n}}\n\n\
+             impl Checksum for {name} {{\n    fn checksum(&self) -> u64 {{\n        match self {{\n{}\n        }}\n    }}\n}}",
+            declarations.join("\n"),
+            arms.join("\n")
+        );
+        self.enums.push(EnumDef { name, variants });
+        item
+    }
+
+    fn function_item(&mut self) -> String {
+        let name = self.fresh("f");
+        let count = self.rng.gen_range(0..=3);
+        let params: Vec<Ty> = (0..count).map(|_| self.random_type(1)).collect();
+        let ret = self.random_type(0);
+        self.scopes.push(vec![]);
+        let mut declarations = vec![];
+        for param in params.iter() {
+            let param_name = self.fresh("p");
+            declarations.push(format!("{}: {}", param_name, self.type_name(param)));
+            self.declare(param_name, param.clone(), false);
+        }
+        let body = self.block(1, 1);
+        // block은 scope를 따로 만들어서 그 안의 변수는 return expression에서 못 쓴다
+        let value = self.expression(&ret, 1);
+        self.scopes.pop();
+        let item = format!(
+            "fn {}({}) -> {} {{\n{}\n    {}\n}}",
+            name,
+            declarations.join(", "),
+            self.type_name(&ret),
+            body.join("\n"),
+            value
+        );
+        // 자기보다 먼저 만든 함수만 부를 수 있어서 재귀가 없다
+        self.fns.push(FnDef { name, params, ret });
+        item
+    }
+
+    fn main_item(&mut self) -> String {
+        self.scopes.push(vec![]);
+        let count = self.rng.gen_range(3..=8);
+        let statements: Vec<String> = (0..count).map(|_| self.statement(1, 1)).collect();
+        let checksums: Vec<String> = self
+            .scopes
+            .last()
+            .unwrap()
+            .iter()
+            .map(|v| {
+                format!(
+                    "    checksum = checksum.wrapping_mul(31).wrapping_add({}.checksum());",
+                    v.name
+                )
+            })
+            .collect();
+        self.scopes.pop();
+        format!(
+            "fn main() {{\n{}\n    let mut checksum: u64 = 0;\n{}\n    println!(\"checksum = {{}}\", checksum);\n}}",
+            statements.join("\n"),
+            checksums.join("\n")
+        )
+    }
+}
+
+// 프로그램 하나: 공통 trait, struct와 enum 몇 개, 함수 max_items 개, checksum을 출력하는 main
+pub fn generate_program(budget: &Budget) -> String {
+    let mut program = Program {
+        budget,
+        rng: rand::thread_rng(),
+        structs: vec![],
+        enums: vec![],
+        fns: vec![],
+        scopes: vec![],
+        next_name: 0,
+    };
+    let mut items = vec![PRELUDE.trim_end().to_string()];
+    for int in INTS {
+        items.push(format!(
+            "impl Checksum for {} {{\n    fn checksum(&self) -> u64 {{\n        *self as u64\n    }}\n}}",
+            int
+        ));
+    }
+    for _ in 0..program.rng.gen_range(0..=2) {
+        items.push(program.struct_item());
+    }
+    for _ in 0..program.rng.gen_range(0..=2) {
+        items.push(program.enum_item());
+    }
+    for _ in 0..budget.max_items {
+        items.push(program.function_item());
+    }
+    items.push(program.main_item());
+    let mut source_code = items.join("\n\n");
+    source_code.push('\n');
+    source_code
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::oracles::{rustc, OracleConfig};
+    use crate::runner::{self, Verdict};
+
+    #[test]
+    fn generated_programs_compile_and_run() {
+        let output_dir = std::env::temp_dir().join(format!("crate-typed-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        let budget = Budget {
+            max_depth: 4,
+            max_items: 3,
+        };
+        for i in 0..5 {
+            let source_code = generate_program(&budget);
+            let path = output_dir.join(format!("typed_{}.rs", i));
+            fs::write(&path, &source_code).unwrap();
+            let compiled = rustc::compile(&config, &path, "typed", &[]);
+            assert_eq!(
+                compiled.run.verdict,
+                Verdict::Accepted,
+                "{}\n{}",
+                source_code,
+                compiled.run.stderr
+            );
+            let run = runner::run_binary(compiled.binary.as_ref().unwrap(), config.timeout);
+            assert_eq!(run.verdict, Verdict::Accepted, "{}", source_code);
+            assert!(run.stdout.starts_with("checksum = "), "{}", run.stdout);
+        }
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
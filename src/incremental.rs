@@ -0,0 +1,217 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::oracles::rustc;
+use crate::oracles::{Finding, OracleConfig, OracleState};
+use crate::runner::{self, Verdict};
+
+// incremental compilation은 관련 있는 코드를 두 번 이상 컴파일해야 버그가 드러난다.
+// seed 하나와 그 mutant들을 순서대로 같은 -C incremental 디렉토리에 컴파일하고,
+// 매 단계마다 incremental 없이 처음부터 컴파일한 결과와 비교한다.
+//
+// sequence는 <output_dir>/incremental/<seed>_<n>/step_<i>.rs 로 저장되고
+// --replay <dir> 로 그대로 다시 돌려볼 수 있다.
+
+// 단계마다 같은 경로에 덮어써야 incremental cache가 이전 단계와 이어진다.
+fn step_source_path(config: &OracleConfig) -> PathBuf {
+    config.work_dir.join("incremental").join("src.rs")
+}
+
+fn cache_dir(config: &OracleConfig) -> PathBuf {
+    config.work_dir.join("incremental").join("cache")
+}
+
+// sequence 하나를 처음부터 컴파일한다. 발견한 것이 있으면 돌려준다.
+pub fn run_sequence(
+    config: &OracleConfig,
+    state: &mut OracleState,
+    steps: &[String],
+) -> Vec<Finding> {
+    let source_path = step_source_path(config);
+    let cache = cache_dir(config);
+    let _ = fs::remove_dir_all(&cache);
+    fs::create_dir_all(&cache).unwrap();
+    let incremental_arg = format!("-Cincremental={}", cache.display());
+
+    let mut findings = vec![];
+    for (i, step) in steps.iter().enumerate() {
+        fs::write(&source_path, step).unwrap();
+        let incr = rustc::compile(config, &source_path, "incr", &[&incremental_arg]);
+        let clean = rustc::compile(config, &source_path, "clean", &[]);
+        state.executions += 1;
+        if clean.run.verdict == Verdict::Accepted {
+            state.accepted += 1;
+        }
+        println!(
+            "[step {}] incremental: {:?}, clean: {:?}",
+            i, incr.run.verdict, clean.run.verdict
+        );
+
+        let incr_codes = runner::error_codes(&incr.run.stderr);
+        let clean_codes = runner::error_codes(&clean.run.stderr);
+        let (kind, signature) = if incr.run.verdict == Verdict::Ice
+            || incr.run.verdict == Verdict::Crash
+        {
+            let kind = if clean.run.verdict == Verdict::Ice {
+                "ice"
+            } else {
+                "incremental-only-ice"
+            };
+            (kind, runner::ice_signature(&incr.run.stderr))
+        } else if incr.run.verdict == Verdict::Timeout || clean.run.verdict == Verdict::Timeout {
+            continue;
+        } else if incr.run.verdict != clean.run.verdict {
+            // 예를 들어 incremental에서만 나는 에러가 clean build에서는 사라지는 경우
+            (
+                "verdict-mismatch",
+                format!(
+                    "{:?} {:?} -> {:?} {:?}",
+                    incr.run.verdict, incr_codes, clean.run.verdict, clean_codes
+                ),
+            )
+        } else if incr_codes != clean_codes {
+            (
+                "diagnostic-mismatch",
+                format!("{:?} -> {:?}", incr_codes, clean_codes),
+            )
+        } else {
+            continue;
+        };
+
+        let mut artifacts: Vec<(String, String)> = steps
+            .iter()
+            .enumerate()
+            .map(|(j, s)| (format!("step_{}.rs", j), s.clone()))
+            .collect();
+        artifacts.push(("incremental.stderr".to_string(), incr.run.stderr.clone()));
+        artifacts.push(("clean.stderr".to_string(), clean.run.stderr.clone()));
+        artifacts.push(("failed_step.txt".to_string(), i.to_string()));
+        findings.push(Finding {
+            oracle: "incremental",
+            kind,
+            signature,
+            artifacts,
+        });
+        // 한 번 어긋나면 이후 단계는 cache 상태를 믿을 수 없다
+        break;
+    }
+    findings
+}
+
+// seed 하나에 대해 mutant들을 sequence_length-1 개씩 묶어 seed 뒤에 이어 붙인다.
+pub fn fuzz_seed(
+    config: &OracleConfig,
+    state: &mut OracleState,
+    output_dir: &Path,
+    file_name: &str,
+    source_code: &str,
+    mutants: &[String],
+    sequence_length: usize,
+) {
+    fs::create_dir_all(config.work_dir.join("incremental")).unwrap();
+    let chunk = sequence_length.max(2) - 1;
+
+    for (n, group) in mutants.chunks(chunk).enumerate() {
+        let mut steps = vec![source_code.to_string()];
+        steps.extend(group.iter().cloned());
+
+        // 나중에 replay 할 수 있도록 sequence를 저장해둔다
+        let sequence_dir = output_dir
+            .join("incremental")
+            .join(format!("{}_{}", file_name, n + 1));
+        fs::create_dir_all(&sequence_dir).unwrap();
+        for (i, step) in steps.iter().enumerate() {
+            fs::write(sequence_dir.join(format!("step_{}.rs", i)), step).unwrap();
+        }
+
+        let last_step = sequence_dir.join(format!("step_{}.rs", steps.len() - 1));
+        for finding in run_sequence(config, state, &steps).iter() {
+            state.record(config, &last_step, finding);
+        }
+    }
+}
+
+// 저장된 sequence 디렉토리(step_0.rs, step_1.rs, ...)를 다시 돌린다.
+// crash bucket 디렉토리에도 step 파일이 같이 저장되니 그대로 넘겨도 된다.
+pub fn replay(config: &OracleConfig, state: &mut OracleState, sequence_dir: &Path) {
+    fs::create_dir_all(config.work_dir.join("incremental")).unwrap();
+    let mut steps = vec![];
+    while let Ok(step) = fs::read_to_string(sequence_dir.join(format!("step_{}.rs", steps.len()))) {
+        steps.push(step);
+    }
+    if steps.is_empty() {
+        panic!("No step_0.rs in {}", sequence_dir.display());
+    }
+    println!(
+        "replaying {} steps from {}",
+        steps.len(),
+        sequence_dir.display()
+    );
+
+    let findings = run_sequence(config, state, &steps);
+    if findings.is_empty() {
+        println!("sequence did not reproduce");
+    }
+    for finding in findings.iter() {
+        println!(
+            "[{}] {} : {}",
+            finding.oracle, finding.kind, finding.signature
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agreeing_steps_have_no_findings() {
+        let output_dir = std::env::temp_dir().join(format!("crate-incr-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        fs::create_dir_all(config.work_dir.join("incremental")).unwrap();
+        let mut state = OracleState::default();
+        let steps = [
+            "fn main() { let x = 1; println!(\"{}\", x); }".to_string(),
+            "fn main() { let x: u8 = \"a\"; }".to_string(),
+            "fn f() -> u8 { 2 }\nfn main() { println!(\"{}\", f()); }".to_string(),
+        ];
+        assert!(run_sequence(&config, &mut state, &steps).is_empty());
+        assert_eq!(state.executions, 3);
+        assert_eq!(state.accepted, 2);
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn sequences_are_saved_for_replay() {
+        let output_dir = std::env::temp_dir().join(format!("crate-seq-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        let mut state = OracleState::default();
+        let mutants: Vec<String> = (1..=3).map(|i| format!("fn main() {{ {}; }}", i)).collect();
+        fuzz_seed(
+            &config,
+            &mut state,
+            &output_dir,
+            "a.rs",
+            "fn main() {}",
+            &mutants,
+            3,
+        );
+        let first = output_dir.join("incremental/a.rs_1");
+        let second = output_dir.join("incremental/a.rs_2");
+        assert_eq!(
+            fs::read_to_string(first.join("step_0.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert_eq!(
+            fs::read_to_string(first.join("step_2.rs")).unwrap(),
+            mutants[1]
+        );
+        assert_eq!(
+            fs::read_to_string(second.join("step_1.rs")).unwrap(),
+            mutants[2]
+        );
+        assert!(!second.join("step_2.rs").exists());
+        assert_eq!(state.executions, 5);
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
@@ -1,3 +1,16 @@
+// 원래 mutator 코드에 있던 clippy 스타일 경고는 고치지 않고 그대로 둔다
+#![allow(
+    clippy::cmp_owned,
+    clippy::comparison_to_empty,
+    clippy::int_plus_one,
+    clippy::map_clone,
+    clippy::needless_borrow,
+    clippy::needless_borrows_for_generic_args,
+    clippy::needless_return,
+    clippy::print_literal,
+    clippy::to_string_in_format_args
+)]
+
 use std::{fs, path::PathBuf};
 
 use clap::Parser;
@@ -7,6 +20,16 @@ use tqdm::tqdm;
 use tree_sitter::{Point, TreeCursor};
 use walkdir::WalkDir;
 
+mod generators;
+mod incremental;
+mod mutators;
+mod oracles;
+mod runner;
+mod schedule;
+mod seeds;
+
+use oracles::{OracleConfig, OracleState};
+
 // tree-sitter가 코드를 분석/parsing해 만든 tree는 굉장히 복잡하다.
 // 그 안 각각의 node는 타입, 코드 시작 위치, 종료 위치, 코드 내용 등을 가지고있다.
 // line_comment [0, 0] - [0, 14]
@@ -95,44 +118,7 @@ pub fn find_type(source_code: &str, cursor: &mut TreeCursor, acc: &mut Vec<TypeP
 }
 */
 
-// 이쪽에서 type을 수정하는 작업을 진행한다
-/*
-pub fn modify_types_my(source_code: &str, structs: &Vec<TypePosInfo>) -> Vec<String> {
-    let mut new_exprs: HashMap<&str, Vec<&str>> = HashMap::new();
-    new_exprs.insert("type_identifier", vec!["", "i32", "str", "Copy"]);
-    new_exprs.insert("unit_type", vec!["", "f32", "char", "Clone", "a"]);
-    new_exprs.insert("identifier", vec!["", "f32", "char", "Clone", "a"]);
-    // Add more type mappings as needed
-
-    let mut modified_versions = Vec::new();
-
-    for &(type_string, start_byte, end_byte, start_point, end_point) in structs.iter() {
-        // start_byte와 end_byte를 이용해 원본 코드를 자른다.
-        // before는 바꾸고자 하는 코드 앞, after는 뒤, original은 바뀌는 부분의 원본 코드
-        let before = &source_code[..start_byte];
-        let after = &source_code[end_byte..];
-        let original = &source_code[start_byte..end_byte];
-
-        // type_string에 해당하는 타입을 찾아서, 그 타입에 해당하는 변형된 버전을 찾아서 modified_versions에 넣어준다.
-        if let Some(exprs) = new_exprs.get(type_string) {
-            for n in exprs {
-                modified_versions.push(format!("{}{}{}", before, n, after));
-                // print difference between original and modified version
-                // _name is not a code. find it with before and after.
-                if n.to_string() != "" {
-                    println!(
-                        "{}-{} {} : {} -> {}",
-                        start_point, end_point, type_string, original, n
-                    );
-                }
-            }
-        }
-    }
-
-    //변형된 버전을 돌려줌.
-    modified_versions
-}
-*/
+// type을 수정하는 mutator는 mutators::types 로 옮겼다. (mode 15)
 
 //comment를 제외한 모든 타입에 대해 돌려준다.
 pub fn find_type_except_comment(
@@ -535,13 +521,44 @@ pub fn get_struct_crushed_sources(
     //tree가 복잡복잡하고 주어진 tree-sitter의 탐색 방법 제한이 커서, vertical과 horizontal로 나눠서 탐색한다.
     visit_vertical(&source_code, &mut tree.walk(), &mut found_structs);
 
-    // modify_types_my(&source_code, &found_structs)
+    mutate_nodes(source_code, &found_structs, mutation_mode, mutation_count)
+}
+
+// 이미 모아둔 node 목록으로 mutant를 만든다. scheduler는 고른 kind의 node만 골라서 넘긴다.
+pub fn mutate_nodes(
+    source_code: &str,
+    structs: &Vec<TypePosInfo>,
+    mutation_mode: i32,
+    mutation_count: i32,
+) -> Vec<String> {
     // mutate_delete_only(&source_code, &found_structs)
     // 입력 옵션을 받아 deletion only 말고 다른것도 하게 만들자.
     if mutation_mode == 0 {
-        return mutate_delete_only(&source_code, &found_structs, mutation_count);
+        mutate_delete_only(source_code, structs, mutation_count)
     } else if mutation_mode == 1 {
-        return mutate_self(&source_code, &found_structs, mutation_count);
+        mutate_self(source_code, structs, mutation_count)
+    } else if mutation_mode == 6 {
+        mutators::literal::mutate_literal(source_code, structs, mutation_count)
+    } else if mutation_mode == 7 {
+        mutators::operator::mutate_operator(source_code, structs, mutation_count)
+    } else if mutation_mode == 8 {
+        mutators::rename::mutate_rename(source_code, structs, mutation_count)
+    } else if mutation_mode == 9 {
+        mutators::generics::mutate_generics(source_code, structs, mutation_count)
+    } else if mutation_mode == 11 {
+        mutators::macros::mutate_macro(source_code, structs, mutation_count)
+    } else if mutation_mode == 14 {
+        mutators::wrap::mutate_wrap(source_code, structs, mutation_count)
+    } else if mutation_mode == 16 {
+        mutators::patterns::mutate_patterns(source_code, structs, mutation_count)
+    } else if mutation_mode == 17 {
+        mutators::consts::mutate_consts(source_code, structs, mutation_count)
+    } else if mutation_mode == 18 {
+        mutators::closures::mutate_closures(source_code, structs, mutation_count)
+    } else if mutation_mode == 19 {
+        mutators::coherence::mutate_coherence(source_code, structs, mutation_count)
+    } else if mutation_mode == 20 {
+        mutators::unsafety::mutate_unsafety(source_code, structs, mutation_count)
     } else {
         panic!("No such mutation mode.");
     }
@@ -587,18 +604,101 @@ struct Cli {
     /// locate output directory path
     #[arg(short, long)]
     output_dir: Option<String>,
-    /// 0: deletion only, 1: self splice mutation, 2: all file splice mutation, 3: all file splice mutation with random type
-    #[arg(short, long)]
-    mode: i32,
+    /// 0: deletion only, 1: self splice mutation, 2: all file splice mutation, 3: all file splice mutation with random type,
+    /// 4: incremental compilation with self splice mutants, 5: incremental compilation with all file splice mutants,
+    /// 6: literal boundary values, 7: operator swapping, 8: scope-aware renaming, 9: lifetime and generic parameters,
+    /// 10: feature gate and attribute injection, 11: macro token trees, 12: node duplication and deep nesting,
+    /// 13: statement and item insertion from all files, 14: wrap and unwrap,
+    /// 15: type substitution, 16: patterns and match arms, 17: const generics and const evaluation, 18: async and closure captures, 19: trait and impl coherence,
+    /// 20: unsafe and raw pointers, 21: grammar-based generation from all files, 22: well-typed program generation without input.
+    /// comma separated modes (e.g. 6,7,14) are mixed per mutant by the scheduler, see --schedule
+    #[arg(short, long, value_delimiter = ',', required_unless_present_any = ["profile", "replay"])]
+    mode: Vec<i32>,
+    /// named mutation profile used instead of --mode.
+    /// unsafe: mode 20, compiled with -Zmir-opt-level=4 -Zvalidate-mir -Copt-level=3 by the oracles
+    #[arg(long, conflicts_with = "mode")]
+    profile: Option<String>,
     /// count of mutation for each seed file.
     /// if 0, then generate all possible mutation files
     #[arg(short, long)]
     file_count: Option<i32>,
+    /// oracles to run on each mutant, comma separated.
+    /// rustc: ICE/crash/timeout, pretty: -Zunpretty round-trip, rustfmt: rustfmt stability,
+    /// miri: undefined behaviour of executable mutants, solver: default vs -Znext-solver differential,
+    /// opt: output of executable mutants with -Copt-level=3 / -Zmir-opt-level=4 differential
+    #[arg(long, value_delimiter = ',')]
+    oracle: Vec<String>,
+    /// rustc binary used by oracles
+    #[arg(long, default_value = "rustc")]
+    rustc: String,
+    /// rustfmt binary used by the rustfmt oracle
+    #[arg(long, default_value = "rustfmt")]
+    rustfmt: String,
+    /// miri driver binary used by the miri oracle
+    #[arg(long, default_value = "miri")]
+    miri: String,
+    /// sysroot for miri (the path printed by `cargo miri setup`)
+    #[arg(long)]
+    miri_sysroot: Option<String>,
+    /// also compare the default trait solver with -Zpolonius in the solver oracle
+    #[arg(long)]
+    polonius: bool,
+    /// timeout in seconds for each process run by oracles
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+    /// number of compilations in one incremental sequence (seed + mutants), used by mode 4 and 5
+    #[arg(long, default_value_t = 2)]
+    sequence_length: usize,
+    /// feature gate dictionary used by mode 10, one gate per line.
+    /// gates in #![feature(..)] of the input files are always added
+    #[arg(long)]
+    feature_dict: Option<String>,
+    /// type dictionary added to the built-in types of mode 15, one type per line.
+    /// {} in a type is replaced with the original type
+    #[arg(long)]
+    type_dict: Option<String>,
+    /// largest number of copies made by node duplication in mode 12
+    #[arg(long, default_value_t = 256)]
+    max_copies: usize,
+    /// largest nesting depth made in mode 12
+    #[arg(long, default_value_t = 256)]
+    max_depth: usize,
+    /// largest size in bytes of the code replaced by one mode 12 mutation
+    #[arg(long, default_value_t = 100_000)]
+    max_growth_bytes: usize,
+    /// largest depth of regenerated nodes in a file generated by mode 21,
+    /// or of expressions and blocks in a program generated by mode 22
+    #[arg(long, default_value_t = 4)]
+    gen_depth: usize,
+    /// number of top-level items in a file generated by mode 21, or of functions besides main generated by mode 22
+    #[arg(long, default_value_t = 5)]
+    gen_items: usize,
+    /// node kind and mode weights for the scheduler, one "kind <kind> <weight>" or "mode <mode> <weight>" per line.
+    /// turns on the scheduler, which picks a mode and a node kind for each mutant
+    #[arg(long)]
+    schedule: Option<String>,
+    /// turn on the scheduler and move weight to modes and node kinds that find new buckets or diagnostics
+    #[arg(long)]
+    adaptive: bool,
+    /// run an evolutionary campaign of this many rounds over a seed queue instead of mutating each seed once.
+    /// productive seeds get more than --file-count mutants, and interesting mutants become new seeds
+    #[arg(long, default_value_t = 0)]
+    rounds: usize,
+    /// largest number of seeds kept in the --rounds queue. after each round the lowest-energy added seeds are evicted
+    #[arg(long, default_value_t = 1000)]
+    max_queue: usize,
+    /// replay a saved incremental sequence directory (step_0.rs, step_1.rs, ...) and exit
+    #[arg(long)]
+    replay: Option<String>,
 }
 
 pub fn main() {
     let args = Cli::parse();
-
+    let profile = args.profile.as_deref().map(mutators::profile);
+    let modes = match profile {
+        Some(profile) => vec![profile.mode],
+        None => args.mode,
+    };
     let output_dir: PathBuf = if let Some(o) = args.output_dir {
         // if directory exists then use it, otherwise create it (and notice it to the user)
         if !PathBuf::from(&o).exists() {
@@ -616,15 +716,99 @@ pub fn main() {
         current_dir
     };
 
-    if let Some(input_dir) = args.input_dir {
-        let mutation_mode = args.mode;
+    // oracle이 하나도 안 켜져 있으면 예전처럼 mutant 파일만 쓴다.
+    let mut oracle_config = OracleConfig::new(args.oracle, &output_dir);
+    oracle_config.rustc = args.rustc;
+    oracle_config.rustfmt = args.rustfmt;
+    oracle_config.miri = args.miri;
+    oracle_config.miri_sysroot = args.miri_sysroot;
+    oracle_config.solver_polonius = args.polonius;
+    oracle_config.timeout = args.timeout;
+    if let Some(profile) = profile {
+        oracle_config.rustc_args = profile.rustc_args.iter().map(|a| a.to_string()).collect();
+    }
+    let mut oracle_state = OracleState::default();
+    // replay는 mode 없이 oracle만 쓰니 아래 검사보다 먼저 끝낸다
+    if let Some(sequence_dir) = args.replay {
+        incremental::replay(&oracle_config, &mut oracle_state, &PathBuf::from(sequence_dir));
+        return;
+    }
+
+    let mode = modes[0];
+    // mode가 여러 개거나 weight를 주면 mutant마다 mode와 node kind를 scheduler가 고른다.
+    // 아래 분기는 첫 mode만 보니, splice나 generator mode가 섞여 있으면 나머지가 무시되지 않게 먼저 멈춘다
+    let mut schedule = if modes.len() > 1 || args.schedule.is_some() || args.adaptive {
+        if let Some(other) = schedule::unschedulable(&modes) {
+            panic!(
+                "Mode {} cannot be mixed with other modes, --schedule or --adaptive. Only modes {:?} can.",
+                other,
+                schedule::SCHEDULED_MODES
+            );
+        }
+        if args.file_count.unwrap_or(0) == 0 {
+            panic!("Scheduling needs --file-count larger than 0.");
+        }
+        Some(schedule::Schedule::new(modes.clone(), args.schedule.as_deref(), args.adaptive))
+    } else {
+        None
+    };
+
+    // seed queue는 oracle 결과로 seed의 성과를 재고, mutant 하나씩 만들 수 있는 mode만 된다
+    if args.rounds > 0 {
+        if oracle_config.oracles.is_empty() {
+            panic!("Evolution needs at least one --oracle.");
+        }
+        if args.file_count.unwrap_or(0) == 0 {
+            panic!("Evolution needs --file-count larger than 0.");
+        }
+        if !schedule::SCHEDULED_MODES.contains(&mode) {
+            panic!("Mode {} cannot be evolved.", mode);
+        }
+    }
+
+    // mode 22는 seed 없이 type이 맞는 프로그램을 file_count개 만든다.
+    if mode == 22 {
+        let mutation_count = args.file_count.unwrap_or(0);
+        if mutation_count == 0 {
+            panic!("Stopped because there might be too much mutated files.");
+        }
+        let budget = generators::Budget {
+            max_depth: args.gen_depth,
+            max_items: args.gen_items,
+        };
+        for idx in tqdm(0..mutation_count as usize).style(tqdm::Style::Block) {
+            let generated = generators::typed::generate_program(&budget);
+            let file_path = output_dir.join(format!("typed_{}.rs", idx + 1));
+            fs::write(&file_path, generated).unwrap();
+            oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+        }
+        println!("Number of generated files: {}", mutation_count);
+    } else if let Some(input_dir) = args.input_dir {
+        let mutation_mode = mode;
         let mutation_count = args.file_count.unwrap_or(0);
+        // mode 10에서 넣을 gate는 파일마다 모으지 않고 처음에 한 번만 모은다.
+        // scheduler는 목록의 어느 mode든 고를 수 있으니 첫 mode만 보지 않는다.
+        let feature_gates = if modes.contains(&10) {
+            mutators::attribute::feature_gates(&input_dir, args.feature_dict.as_deref())
+        } else {
+            vec![]
+        };
+        let type_dict = if modes.contains(&15) {
+            mutators::types::type_dictionary(args.type_dict.as_deref())
+        } else {
+            vec![]
+        };
+        let growth = mutators::growth::Growth {
+            max_copies: args.max_copies,
+            max_depth: args.max_depth,
+            max_bytes: args.max_growth_bytes,
+        };
 
         // 여긴 모든 파일로부터 mutation splice code를 얻어오게 시킨다.
         // 이걸 하려면 1. input_dir 내 모든 entry에 대해 mutate_self에 있던 new_exprs.insert를 실행해
         // 아주아주 거대한 new_exprs를 만든 다음
         // 2. mutate_self와 유사한 방법으로 각 파일을 mutate.
-        if mutation_mode == 2 || mutation_mode == 3 {
+        if mutation_mode == 2 || mutation_mode == 3 || mutation_mode == 5 || mutation_mode == 13 || mutation_mode == 21 {
             if mutation_count == 0 {
                 panic!("Stopped because there might be too much mutated files.");
             }
@@ -679,99 +863,243 @@ pub fn main() {
                     }
                 }
             }
+            // mode 21은 seed를 변이하지 않고, 모은 splice code로 새 파일을 mutation_count개 만든다.
+            if mutation_mode == 21 {
+                let grammar = generators::grammar::Grammar::new();
+                let budget = generators::Budget {
+                    max_depth: args.gen_depth,
+                    max_items: args.gen_items,
+                };
+                for idx in tqdm(0..mutation_count as usize).style(tqdm::Style::Block) {
+                    let generated = generators::grammar::generate_file(&grammar, &new_expressions, &budget);
+                    let file_path = output_dir.join(format!("gen_{}.rs", idx + 1));
+                    fs::write(&file_path, generated).unwrap();
+                    oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+                }
+                println!("Number of generated files: {}", mutation_count);
+            }
             // 이제 진짜 파일 단위의 변이.
             // 위에서 input_dir를 소모해버림. 이대로는 안된다.
             // 그래서, 위쪽에서 input_dir를 가져갈 때 &를 붙여 잠깐 가져가게 했다.
-            for entry in tqdm(WalkDir::new(input_dir).into_iter()).style(tqdm::Style::Block) {
-                // 최종 결과를 담는게 mutated
-                let mut mutated: Vec<String> = vec![];
-                // 각 파일의 Vec<TypePosInfo>를 담는게 struct_per_file
-                let mut struct_per_file: Vec<TypePosInfo> = vec![];
-                let entry = entry.unwrap();
-                let path = entry.path();
-                // file name을 저장한다.
-                if let Some(ext) = path.extension() {
-                    let name = path.file_name().unwrap().to_string_lossy().into_owned();
-                    println!("filename : {}", name);
-                    if path.is_file() && ext.to_string_lossy() == "rs" {
-                        // dbg!(path);
-                        // if source_code is larger then 500 lines, ignore it.
-                        if let Ok(source_code) = fs::read_to_string(path){
-                            if source_code.lines().count() < 500 { // 너무 큰 파일 안씀
-                                struct_per_file.append(&mut get_splice_parts(&source_code));
-                                if mutation_mode == 3 {
-                                    mutated.append(&mut mutate_splice_randtype(
-                                        &source_code,
-                                        &new_expressions,
-                                        &struct_per_file,
-                                        mutation_count,
-                                    ));
-                                } else { /* mutation_mode == 2 */
-                                    mutated.append(&mut mutate_splice(
-                                        &source_code,
-                                        &new_expressions,
-                                        &struct_per_file,
-                                        mutation_count,
-                                    ));
+            else {
+                for entry in tqdm(WalkDir::new(input_dir).into_iter()).style(tqdm::Style::Block) {
+                    // 최종 결과를 담는게 mutated
+                    let mut mutated: Vec<String> = vec![];
+                    // 각 파일의 Vec<TypePosInfo>를 담는게 struct_per_file
+                    let mut struct_per_file: Vec<TypePosInfo> = vec![];
+                    let entry = entry.unwrap();
+                    let path = entry.path();
+                    // file name을 저장한다.
+                    if let Some(ext) = path.extension() {
+                        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                        println!("filename : {}", name);
+                        if path.is_file() && ext.to_string_lossy() == "rs" {
+                            // dbg!(path);
+                            // if source_code is larger then 500 lines, ignore it.
+                            if let Ok(source_code) = fs::read_to_string(path){
+                                if source_code.lines().count() < 500 { // 너무 큰 파일 안씀
+                                    struct_per_file.append(&mut get_splice_parts(&source_code));
+                                    if mutation_mode == 3 {
+                                        mutated.append(&mut mutate_splice_randtype(
+                                            &source_code,
+                                            &new_expressions,
+                                            &struct_per_file,
+                                            mutation_count,
+                                        ));
+                                    } else if mutation_mode == 5 {
+                                        // mutant는 파일로 쓰지 않고 seed 뒤에 이어서 incremental 컴파일
+                                        let mutants = mutate_splice(
+                                            &source_code,
+                                            &new_expressions,
+                                            &struct_per_file,
+                                            mutation_count,
+                                        );
+                                        incremental::fuzz_seed(
+                                            &oracle_config,
+                                            &mut oracle_state,
+                                            &output_dir,
+                                            &name,
+                                            &source_code,
+                                            &mutants,
+                                            args.sequence_length,
+                                        );
+                                    } else if mutation_mode == 13 {
+                                        mutated.append(&mut mutators::insert::mutate_insert(
+                                            &source_code,
+                                            &new_expressions,
+                                            &struct_per_file,
+                                            mutation_count,
+                                        ));
+                                    } else { /* mutation_mode == 2 */
+                                        mutated.append(&mut mutate_splice(
+                                            &source_code,
+                                            &new_expressions,
+                                            &struct_per_file,
+                                            mutation_count,
+                                        ));
+                                    }
                                 }
                             }
                         }
                     }
-                }
-                println!("testing2");
-                for (idx, src) in mutated.iter().enumerate() {
+                    println!("testing2");
+                    for (idx, src) in mutated.iter().enumerate() {
+                        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+                        let file_name = format!("mut_{}_{}.rs", file_name, (idx + 1).to_string());
+                        let file_path = output_dir.join(file_name);
+                        fs::write(&file_path, src).unwrap();
+                        oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+                    }
                     let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
-                    let file_name = format!("mut_{}_{}.rs", file_name, (idx + 1).to_string());
-                    let file_path = output_dir.join(file_name);
-                    fs::write(file_path, src).unwrap();
+                    println!(
+                        "Number of generated files of {}: {}",
+                        file_name,
+                        mutated.len()
+                    );
                 }
-                let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
-                println!(
-                    "Number of generated files of {}: {}",
-                    file_name,
-                    mutated.len()
-                );
             }
         }
         // 여긴 통상적으로 진행
         else {
-            for entry in tqdm(WalkDir::new(input_dir).into_iter()).style(tqdm::Style::Block) {
-                let mut mutated: Vec<String> = vec![];
-                let entry = entry.unwrap();
-                let path = entry.path();
-                // file name을 저장한다.
-                let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
-                if let Some(ext) = path.extension() {
-                    let name = path.file_name().unwrap().to_string_lossy().into_owned();
-                    println!("filename : {}", name);
-                    if path.is_file() && ext.to_string_lossy() == "rs" {
-                        // dbg!(path);
-                        if let Ok(source_code) = fs::read_to_string(path){
-                            if source_code.lines().count() < 500 { // 너무 큰 파일 안씀
-                                println!("testing1");
-                                mutated.append(&mut get_struct_crushed_sources(
-                                    &source_code,
-                                    mutation_mode,
-                                    mutation_count,
-                                ));
+            // mode 10, 12, 15는 node 말고도 따로 모아둔 것을 같이 넘긴다
+            let mutate = |source_code: &str, nodes: &Vec<TypePosInfo>, mode: i32, count: i32| {
+                if mode == 10 {
+                    mutators::attribute::mutate_attribute(source_code, nodes, &feature_gates, count)
+                } else if mode == 12 {
+                    mutators::growth::mutate_growth(source_code, nodes, &growth, count)
+                } else if mode == 15 {
+                    mutators::types::mutate_types(source_code, nodes, &type_dict, count)
+                } else {
+                    mutate_nodes(source_code, nodes, mode, count)
+                }
+            };
+            if args.rounds > 0 {
+                // seed를 한 번씩 변이하고 끝내지 않고 seed queue를 rounds 번 돈다.
+                // round마다 seed의 energy만큼 mutant를 만들고, 흥미로운 mutant는 queue에 seed로 추가한다.
+                // solver oracle을 쓰면 trait 관련 node가 많은 seed를 더 많이 변이한다
+                let trait_weighted = oracle_config.oracles.iter().any(|o| o == "solver");
+                let mut queue = seeds::SeedQueue::new(&input_dir, mutation_count as usize, trait_weighted, args.max_queue, &output_dir);
+                for round in 0..args.rounds {
+                    // 이번 round에 추가된 seed는 다음 round부터 돈다
+                    for index in 0..queue.seeds.len() {
+                        let energy = queue.energy(index);
+                        let name = queue.seeds[index].name.clone();
+                        let source_code = queue.seeds[index].source_code.clone();
+                        let nodes = get_splice_parts(&source_code);
+                        let mut batch = match schedule {
+                            Some(_) => vec![],
+                            None => mutate(&source_code, &nodes, mutation_mode, energy as i32),
+                        };
+                        for idx in 0..energy {
+                            let (src, picked) = match schedule.as_ref() {
+                                Some(schedule) => match schedule.next(&nodes, |op, nodes| mutate(&source_code, nodes, op, 1)) {
+                                    Some((op, kind, src)) => (src, Some((op, kind))),
+                                    None => break,
+                                },
+                                None => match batch.pop() {
+                                    Some(src) => (src, None),
+                                    None => break,
+                                },
+                            };
+                            let file_path = output_dir.join(format!("mut_{}_r{}_{}.rs", name, round + 1, idx + 1));
+                            fs::write(&file_path, &src).unwrap();
+                            let feedback = oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+                            if let (Some(schedule), Some((op, kind))) = (schedule.as_mut(), picked) {
+                                schedule.reward(op, kind, feedback.interesting());
+                            }
+                            queue.record(index, &feedback);
+                            if feedback.interesting() {
+                                queue.add(index, src);
                             }
                         }
                     }
+                    queue.evict();
+                    println!("Round {}: {} seeds", round + 1, queue.seeds.len());
                 }
-                println!("testing2");
-                for (idx, src) in mutated.iter().enumerate() {
-                    let file_name = format!("mut_{}_{}.rs", file_name, (idx + 1).to_string());
-                    let file_path = output_dir.join(file_name);
-                    fs::write(file_path, src).unwrap();
+                queue.print_summary();
+            } else {
+                for entry in tqdm(WalkDir::new(input_dir).into_iter()).style(tqdm::Style::Block) {
+                    let mut mutated: Vec<String> = vec![];
+                    // scheduler가 만들어서 바로 쓴 mutant 수
+                    let mut scheduled = 0;
+                    let entry = entry.unwrap();
+                    let path = entry.path();
+                    // file name을 저장한다.
+                    let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+                    if let Some(ext) = path.extension() {
+                        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                        println!("filename : {}", name);
+                        if path.is_file() && ext.to_string_lossy() == "rs" {
+                            // dbg!(path);
+                            if let Ok(source_code) = fs::read_to_string(path){
+                                if source_code.lines().count() < 500 { // 너무 큰 파일 안씀
+                                    println!("testing1");
+                                    if mutation_mode == 4 {
+                                        // mutate_self로 만든 mutant를 seed 뒤에 이어서 incremental 컴파일
+                                        let mutants = get_struct_crushed_sources(
+                                            &source_code,
+                                            1,
+                                            mutation_count,
+                                        );
+                                        incremental::fuzz_seed(
+                                            &oracle_config,
+                                            &mut oracle_state,
+                                            &output_dir,
+                                            &file_name,
+                                            &source_code,
+                                            &mutants,
+                                            args.sequence_length,
+                                        );
+                                    } else if let Some(schedule) = schedule.as_mut() {
+                                        // mutant를 하나씩 만들어 바로 oracle에 돌리고, 결과를 scheduler에 알려준다
+                                        let nodes = get_splice_parts(&source_code);
+                                        while scheduled < mutation_count as usize {
+                                            let Some((op, kind, src)) = schedule.next(&nodes, |op, nodes| {
+                                                mutate(&source_code, nodes, op, 1)
+                                            }) else {
+                                                break;
+                                            };
+                                            scheduled += 1;
+                                            let file_path = output_dir.join(format!("mut_{}_{}.rs", file_name, scheduled));
+                                            fs::write(&file_path, src).unwrap();
+                                            let feedback = oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+                                            schedule.reward(op, kind, feedback.interesting());
+                                        }
+                                    } else {
+                                        mutated.append(&mut mutate(
+                                            &source_code,
+                                            &get_splice_parts(&source_code),
+                                            mutation_mode,
+                                            mutation_count,
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    println!("testing2");
+                    for (idx, src) in mutated.iter().enumerate() {
+                        let file_name = format!("mut_{}_{}.rs", file_name, (idx + 1).to_string());
+                        let file_path = output_dir.join(file_name);
+                        fs::write(&file_path, src).unwrap();
+                        oracles::check_mutant(&oracle_config, &mut oracle_state, &file_path);
+                    }
+                    println!(
+                        "Number of generated files of {}: {}",
+                        file_name,
+                        mutated.len() + scheduled
+                    );
                 }
-                println!(
-                    "Number of generated files of {}: {}",
-                    file_name,
-                    mutated.len()
-                );
             }
         }
     } else {
         panic!("No input file or directory provided");
     };
+
+    if !oracle_config.oracles.is_empty() || mode == 4 || mode == 5 {
+        oracle_state.print_summary();
+    }
+    if let Some(schedule) = schedule {
+        schedule.print_summary();
+    }
 }
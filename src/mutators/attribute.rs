@@ -0,0 +1,297 @@
+use std::fs;
+
+use rand::seq::SliceRandom;
+use walkdir::WalkDir;
+
+use super::{find_node, node_text, parse};
+use crate::TypePosInfo;
+
+// feature gate와 attribute를 끼워 넣는 mutator.
+// nightly ICE는 대부분 #![feature(..)] 가 있어야 나오는데 seed에는 gate가 없는 경우가 많다.
+// crate 맨 앞에 gate를 하나씩 넣고, item 앞에 attribute를 붙이거나 있던 attribute를 바꾼다.
+
+// 붙일 attribute와, 그 attribute를 쓰려면 같이 켜야 하는 gate
+const ITEM_ATTRIBUTES: [(&str, &str); 28] = [
+    ("#[inline(always)]", ""),
+    ("#[inline(never)]", ""),
+    ("#[cold]", ""),
+    ("#[track_caller]", ""),
+    ("#[repr(packed)]", ""),
+    ("#[repr(C)]", ""),
+    ("#[repr(transparent)]", ""),
+    ("#[repr(align(4096))]", ""),
+    ("#[repr(u8)]", ""),
+    ("#[repr(simd)]", "repr_simd"),
+    (
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]",
+        "",
+    ),
+    ("#[derive(Clone, Copy)]", ""),
+    ("#[cfg(any())]", ""),
+    ("#[cfg(all())]", ""),
+    ("#[cfg_attr(all(), inline)]", ""),
+    ("#[no_mangle]", ""),
+    ("#[export_name = \"main\"]", ""),
+    ("#[must_use]", ""),
+    ("#[non_exhaustive]", ""),
+    ("#[target_feature(enable = \"avx2\")]", ""),
+    ("#[naked]", "naked_functions"),
+    ("#[coverage(off)]", "coverage_attribute"),
+    ("#[rustc_layout(debug)]", "rustc_attrs"),
+    ("#[rustc_variance]", "rustc_attrs"),
+    ("#[rustc_layout_scalar_valid_range_start(1)]", "rustc_attrs"),
+    ("#[rustc_nonnull_optimization_guaranteed]", "rustc_attrs"),
+    ("#[rustc_coinductive]", "rustc_attrs"),
+    ("#[rustc_deny_explicit_impl]", "rustc_attrs"),
+];
+
+// attribute를 앞에 붙일 node들
+const ITEM_KINDS: [&str; 18] = [
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "trait_item",
+    "impl_item",
+    "mod_item",
+    "const_item",
+    "static_item",
+    "type_item",
+    "macro_definition",
+    "field_declaration",
+    "enum_variant",
+    "let_declaration",
+    "expression_statement",
+    "match_arm",
+    "parameter",
+];
+
+// dictionary 파일과 input_dir의 모든 #![feature(..)] 에서 gate 이름을 모은다.
+// dictionary는 한 줄에 gate 하나, #으로 시작하는 줄은 무시한다.
+pub fn feature_gates(input_dir: &str, dict: Option<&str>) -> Vec<String> {
+    let mut gates: Vec<String> = vec![];
+    if let Some(dict) = dict {
+        let text = fs::read_to_string(dict).expect("Cannot read feature dictionary.");
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') && !gates.iter().any(|g| g == line) {
+                gates.push(line.to_string());
+            }
+        }
+    }
+    for entry in WalkDir::new(input_dir).into_iter().flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().map(|e| e != "rs").unwrap_or(true) {
+            continue;
+        }
+        let Ok(source_code) = fs::read_to_string(path) else {
+            continue;
+        };
+        for gate in harvest(&source_code) {
+            if !gates.contains(&gate) {
+                gates.push(gate);
+            }
+        }
+    }
+    gates
+}
+
+// 파일 맨 앞의 #![feature(..)] 에 켜져 있는 gate 이름들
+pub fn harvest(source_code: &str) -> Vec<String> {
+    let tree = parse(source_code);
+    let mut gates = vec![];
+    let mut cursor = tree.root_node().walk();
+    for item in tree.root_node().children(&mut cursor) {
+        if item.kind() != "inner_attribute_item" {
+            continue;
+        }
+        let text = node_text(source_code, item);
+        let Some(list) = text
+            .strip_prefix("#![")
+            .and_then(|t| t.trim_start().strip_prefix("feature"))
+            .map(|t| t.trim().trim_end_matches(']').trim())
+            .and_then(|t| t.strip_prefix('('))
+            .and_then(|t| t.strip_suffix(')'))
+        else {
+            continue;
+        };
+        for gate in list.split(',').map(str::trim) {
+            if !gate.is_empty() {
+                gates.push(gate.to_string());
+            }
+        }
+    }
+    gates
+}
+
+// 바꿀 범위(start..end)와 새 코드, 같이 넣을 gate
+struct Edit<'a> {
+    info: Option<TypePosInfo<'a>>,
+    start: usize,
+    end: usize,
+    new: String,
+    gate: &'a str,
+    note: String,
+}
+
+pub fn mutate_attribute(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    gates: &[String],
+    mutation_count: i32,
+) -> Vec<String> {
+    let tree = parse(source_code);
+    let mut candidates: Vec<Edit> = vec![];
+
+    // crate 맨 앞에 gate 하나 넣기. 이미 켜져 있는 gate는 건너뛴다
+    let existing = harvest(source_code);
+    for gate in gates.iter().filter(|g| !existing.contains(g)) {
+        candidates.push(Edit {
+            info: None,
+            start: 0,
+            end: 0,
+            new: String::new(),
+            gate,
+            note: "feature gate".to_string(),
+        });
+    }
+
+    for info in structs.iter() {
+        let Some(node) = find_node(&tree, info) else {
+            continue;
+        };
+        let text = node_text(source_code, node);
+        if node.kind() == "attribute_item" {
+            // 있던 attribute를 다른 것으로 바꾸기
+            for &(attr, gate) in ITEM_ATTRIBUTES.iter() {
+                if attr != text {
+                    candidates.push(Edit {
+                        info: Some(*info),
+                        start: node.start_byte(),
+                        end: node.end_byte(),
+                        new: attr.to_string(),
+                        gate,
+                        note: format!("replace with {}", attr),
+                    });
+                }
+            }
+        } else if ITEM_KINDS.contains(&node.kind()) {
+            for &(attr, gate) in ITEM_ATTRIBUTES.iter() {
+                candidates.push(Edit {
+                    info: Some(*info),
+                    start: node.start_byte(),
+                    end: node.end_byte(),
+                    new: format!("{} {}", attr, text),
+                    gate,
+                    note: format!("add {}", attr),
+                });
+            }
+        }
+    }
+
+    let chosen: Vec<&Edit> = if mutation_count == 0 {
+        candidates.iter().collect()
+    } else {
+        (0..mutation_count)
+            .filter_map(|_| candidates.choose(&mut rand::thread_rng()))
+            .collect()
+    };
+
+    let mut modified_versions = Vec::new();
+    for (index, edit) in chosen.into_iter().enumerate() {
+        // attribute가 gate를 요구하면 gate도 같이 켠다
+        let gate = if edit.gate.is_empty() || existing.iter().any(|g| g == edit.gate) {
+            String::new()
+        } else {
+            format!("#![feature({})]\n", edit.gate)
+        };
+        modified_versions.push(format!(
+            "{}{}{}{}",
+            gate,
+            &source_code[..edit.start],
+            edit.new,
+            &source_code[edit.end..]
+        ));
+        match edit.info {
+            Some((type_string, start_byte, end_byte, start_point, end_point)) => println!(
+                "[{}] {}-{} {} ({}) : {} -> {}",
+                index + 1,
+                start_point,
+                end_point,
+                type_string,
+                edit.note,
+                &source_code[start_byte..end_byte],
+                edit.new
+            ),
+            None => println!(
+                "[{}] source_file ({}) : #![feature({})]",
+                index + 1,
+                edit.note,
+                edit.gate
+            ),
+        }
+    }
+    modified_versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str, gates: &[&str]) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        let gates: Vec<String> = gates.iter().map(|g| g.to_string()).collect();
+        mutate_attribute(source_code, &nodes, &gates, 0)
+    }
+
+    #[test]
+    fn harvest_reads_crate_feature_gates() {
+        let source_code = "#![feature(never_type, box_patterns)]\n#![feature( decl_macro )]\n#![allow(unused)]\nfn main() {}";
+        assert_eq!(
+            harvest(source_code),
+            ["never_type", "box_patterns", "decl_macro"]
+        );
+        assert!(harvest("fn main() { #[feature(x)] let a = 1; }").is_empty());
+    }
+
+    #[test]
+    fn feature_gates_merges_dictionary_and_inputs() {
+        let dir = std::env::temp_dir().join(format!("crate-gates-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("inputs")).unwrap();
+        fs::write(
+            dir.join("gates.txt"),
+            "# comment\nnever_type\n\nrepr_simd\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("inputs/a.rs"),
+            "#![feature(never_type, generic_const_exprs)]",
+        )
+        .unwrap();
+        fs::write(dir.join("inputs/b.txt"), "#![feature(ignored)]").unwrap();
+        let gates = feature_gates(
+            dir.join("inputs").to_str().unwrap(),
+            Some(dir.join("gates.txt").to_str().unwrap()),
+        );
+        assert_eq!(gates, ["never_type", "repr_simd", "generic_const_exprs"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn gates_are_added_once() {
+        let source_code = "#![feature(never_type)]\nfn f() {}";
+        let all = mutants(source_code, &["never_type", "decl_macro"]);
+        assert!(all.contains(&format!("#![feature(decl_macro)]\n{}", source_code)));
+        assert!(!all.contains(&format!("#![feature(never_type)]\n{}", source_code)));
+    }
+
+    #[test]
+    fn attributes_are_added_and_replaced_with_their_gates() {
+        let all = mutants("#[inline]\nfn f() {}", &[]);
+        assert!(all.contains(&"#[cold]\nfn f() {}".to_string()));
+        assert!(all.contains(&"#![feature(naked_functions)]\n#[naked]\nfn f() {}".to_string()));
+        assert!(all.contains(&"#[inline]\n#[track_caller] fn f() {}".to_string()));
+    }
+}
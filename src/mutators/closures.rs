@@ -0,0 +1,402 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_gates, node_text, Replacement};
+use crate::TypePosInfo;
+
+// async/await와 closure capture를 바꾸는 mutator.
+// async closure와 upvar capture 분석은 ICE가 자주 나는 곳이다
+// (example_data/rustc_ui_codes/async-closure-drop.rs, different-projection-lengths-for-different-upvars.rs).
+// fn <-> async fn, closure의 async/move, call 뒤의 .await, closure가 잡는 변수의
+// projection과 reference/move, Fn/FnMut/FnOnce 를 바꾼다.
+
+const CLOSURE_PREFIXES: [&str; 4] = ["", "move ", "async ", "async move "];
+
+// async closure와 AsyncFn* 은 stable이지만 `async Fn` 처럼 쓰는 bound는 아직 async_trait_bounds가 필요하다
+const FN_TRAITS: [&str; 9] = [
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "AsyncFn",
+    "AsyncFnMut",
+    "AsyncFnOnce",
+    "async Fn",
+    "async FnMut",
+    "async FnOnce",
+];
+
+// closure가 잡은 변수 x 하나를 바꾸는 방법들. {} 자리에 x 가 들어간다
+const CAPTURE_USES: [&str; 6] = [
+    "&{}",
+    "&mut {}",
+    "{ {} }",
+    "{}.clone()",
+    "{}.0",
+    "({}, {}).0",
+];
+
+// closure 본문 맨 앞에 넣어서 capture 방식을 바꾸는 문장들
+const CAPTURE_STATEMENTS: [&str; 5] = [
+    "let _ = &{};",
+    "let _ = &mut {};",
+    "drop({});",
+    "let _ = {}.0;",
+    "let _ = &{}.0.0;",
+];
+
+fn ancestor<'t>(node: Node<'t>, kinds: &[&str]) -> Option<Node<'t>> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if kinds.contains(&n.kind()) {
+            return Some(n);
+        }
+        current = n.parent();
+    }
+    None
+}
+
+fn descendants(node: Node) -> Vec<Node> {
+    let mut out = vec![];
+    let mut stack = vec![node];
+    while let Some(n) = stack.pop() {
+        out.push(n);
+        let mut cursor = n.walk();
+        stack.extend(n.children(&mut cursor));
+    }
+    out
+}
+
+// node 안에서 let, parameter, closure parameter, for, match arm 이 binding 하는 이름들
+fn bindings(source_code: &str, node: Node) -> Vec<String> {
+    let mut names = vec![];
+    for n in descendants(node) {
+        let pattern = match n.kind() {
+            "let_declaration" | "parameter" | "for_expression" | "match_arm" | "let_condition" => {
+                n.child_by_field_name("pattern")
+            }
+            "closure_parameters" => Some(n),
+            _ => None,
+        };
+        let Some(pattern) = pattern else {
+            continue;
+        };
+        for ident in descendants(pattern) {
+            if ident.kind() == "identifier" {
+                let name = node_text(source_code, ident).to_string();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    names
+}
+
+// 값으로 쓰이는 identifier인지. 함수 이름, macro 이름, field 이름은 뺀다
+fn is_value_use(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if parent.kind() == "call_expression" && parent.child_by_field_name("function") == Some(node) {
+        return false;
+    }
+    parent.kind().ends_with("_expression")
+        || matches!(parent.kind(), "arguments" | "block" | "let_declaration")
+}
+
+// closure 바깥의 함수에서 binding 되었고, closure 안에서는 binding 되지 않은 변수들
+fn captured(source_code: &str, closure: Node) -> Vec<String> {
+    let Some(function) = ancestor(closure, &["function_item"]) else {
+        return vec![];
+    };
+    let inner = bindings(source_code, closure);
+    let outer: Vec<String> = bindings(source_code, function)
+        .into_iter()
+        .filter(|name| !inner.contains(name))
+        .collect();
+    let mut names = vec![];
+    for n in descendants(closure) {
+        if n.kind() == "identifier" && is_value_use(n) {
+            let name = node_text(source_code, n).to_string();
+            if outer.contains(&name) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+// fn <-> async fn. function_modifiers 의 순서는 const async unsafe extern 이다
+fn function_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    let Some(modifiers) = children.iter().find(|c| c.kind() == "function_modifiers") else {
+        return match children.iter().find(|c| c.kind() == "fn") {
+            Some(fn_keyword) => vec![(
+                format!(
+                    "{}async {}",
+                    &source_code[node.start_byte()..fn_keyword.start_byte()],
+                    &source_code[fn_keyword.start_byte()..node.end_byte()]
+                ),
+                "fn -> async fn".to_string(),
+                "",
+            )],
+            None => vec![],
+        };
+    };
+    let mut tokens: Vec<&str> = node_text(source_code, *modifiers)
+        .split_whitespace()
+        .collect();
+    let note = match tokens.iter().position(|t| *t == "async") {
+        Some(i) => {
+            tokens.remove(i);
+            "async fn -> fn"
+        }
+        None => {
+            let at = if tokens.first() == Some(&"const") {
+                1
+            } else {
+                0
+            };
+            tokens.insert(at, "async");
+            "fn -> async fn"
+        }
+    };
+    let new_modifiers = tokens.join(" ");
+    let rest = &source_code[modifiers.end_byte()..node.end_byte()];
+    vec![(
+        format!(
+            "{}{}{}",
+            &source_code[node.start_byte()..modifiers.start_byte()],
+            new_modifiers,
+            if new_modifiers.is_empty() {
+                rest.trim_start()
+            } else {
+                rest
+            }
+        ),
+        note.to_string(),
+        "",
+    )]
+}
+
+fn closure_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let (Some(params), Some(body)) = (
+        node.child_by_field_name("parameters"),
+        node.child_by_field_name("body"),
+    ) else {
+        return exprs;
+    };
+    let prefix = &source_code[node.start_byte()..params.start_byte()];
+    let rest = &source_code[params.start_byte()..node.end_byte()];
+    // 이 grammar는 async |x| 의 async를 closure 바깥의 ERROR로 읽는다
+    let already_async = source_code[..node.start_byte()]
+        .trim_end()
+        .ends_with("async");
+    for new_prefix in CLOSURE_PREFIXES {
+        if new_prefix == prefix || (already_async && new_prefix.starts_with("async")) {
+            continue;
+        }
+        exprs.push((
+            format!("{}{}", new_prefix, rest),
+            format!("closure prefix '{}'", new_prefix.trim()),
+            "",
+        ));
+    }
+
+    // 본문 맨 앞에 잡은 변수를 쓰는 문장을 넣어서 capture 방식을 바꾼다
+    let body_text = node_text(source_code, body);
+    let head = &source_code[node.start_byte()..body.start_byte()];
+    for name in captured(source_code, node) {
+        for statement in CAPTURE_STATEMENTS {
+            let statement = statement.replace("{}", &name);
+            let new_body = if body.kind() == "block" {
+                format!("{{ {}{}", statement, &body_text[1..])
+            } else {
+                format!("{{ {} {} }}", statement, body_text)
+            };
+            exprs.push((
+                format!("{}{}", head, new_body),
+                format!("capture {}", statement),
+                "",
+            ));
+        }
+    }
+    exprs
+}
+
+// closure 안에서 바깥 변수를 쓰는 자리: x 와 x.field
+fn capture_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let Some(closure) = ancestor(node, &["closure_expression"]) else {
+        return exprs;
+    };
+    let text = node_text(source_code, node);
+    let captured = captured(source_code, closure);
+    match node.kind() {
+        "identifier" if is_value_use(node) && captured.iter().any(|c| c == text) => {
+            // x.field 의 x 는 field_expression 쪽에서 바꾼다
+            if node.parent().map(|p| p.kind()) == Some("field_expression") {
+                return exprs;
+            }
+            for capture_use in CAPTURE_USES {
+                exprs.push((
+                    capture_use.replace("{}", text),
+                    format!("capture {}", capture_use),
+                    "",
+                ));
+            }
+        }
+        "field_expression" => {
+            // x.method() 의 x.method 는 field가 아니다
+            let is_method = node
+                .parent()
+                .map(|p| p.child_by_field_name("function") == Some(node))
+                .unwrap_or(false);
+            let Some(value) = node.child_by_field_name("value").filter(|_| !is_method) else {
+                return exprs;
+            };
+            let mut root = value;
+            while root.kind() == "field_expression" {
+                match root.child_by_field_name("value") {
+                    Some(inner) => root = inner,
+                    None => return exprs,
+                }
+            }
+            let root_text = node_text(source_code, root);
+            if root.kind() != "identifier" || !captured.iter().any(|c| c == root_text) {
+                return exprs;
+            }
+            let value_text = node_text(source_code, value);
+            // projection을 짧게, 길게, 바깥 변수 전체를 move
+            exprs.push((value_text.to_string(), "shorter projection".to_string(), ""));
+            exprs.push((format!("{}.0", text), "longer projection".to_string(), ""));
+            exprs.push((
+                format!("{{ {} }}{}", root_text, &text[root_text.len()..]),
+                "move whole upvar".to_string(),
+                "",
+            ));
+            exprs.push((format!("&{}", text), "borrow projection".to_string(), ""));
+            exprs.push((
+                format!("&mut {}", text),
+                "mutably borrow projection".to_string(),
+                "",
+            ));
+            exprs.push((format!("{{ {} }}", text), "move projection".to_string(), ""));
+        }
+        _ => {}
+    }
+    exprs
+}
+
+fn closures_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "function_item" | "function_signature_item" => function_replacements(source_code, node),
+        "closure_expression" => closure_replacements(source_code, node),
+        "call_expression" => {
+            let mut exprs = vec![];
+            if node.parent().map(|p| p.kind()) != Some("await_expression") {
+                exprs.push((format!("{}.await", text), "add .await".to_string(), ""));
+                exprs.push((
+                    format!("async {{ {} }}.await", text),
+                    "add async block .await".to_string(),
+                    "",
+                ));
+            }
+            // f() 를 호출하는 쪽에서 Fn/FnMut/FnOnce 중 무엇이 필요한지 바꾼다
+            if let Some(function) = node.child_by_field_name("function") {
+                if function.kind() == "identifier" {
+                    let name = node_text(source_code, function);
+                    let args = &source_code[function.end_byte()..node.end_byte()];
+                    for (callee, note) in [
+                        (format!("(&{})", name), "call through &"),
+                        (format!("(&mut {})", name), "call through &mut"),
+                        (format!("{{ {} }}", name), "call by move"),
+                    ] {
+                        exprs.push((format!("{}{}", callee, args), note.to_string(), ""));
+                    }
+                }
+            }
+            exprs
+        }
+        "await_expression" => {
+            let mut cursor = node.walk();
+            let inner = node.named_children(&mut cursor).next();
+            match inner {
+                Some(inner) => vec![
+                    (
+                        node_text(source_code, inner).to_string(),
+                        "remove .await".to_string(),
+                        "",
+                    ),
+                    (format!("{}.await", text), "double .await".to_string(), ""),
+                ],
+                None => vec![],
+            }
+        }
+        "function_type" => {
+            let Some(trait_name) = node.child_by_field_name("trait") else {
+                return vec![];
+            };
+            let old = node_text(source_code, trait_name);
+            if !FN_TRAITS.contains(&old) {
+                return vec![];
+            }
+            let rest = &source_code[trait_name.end_byte()..node.end_byte()];
+            let already_async = source_code[..node.start_byte()]
+                .trim_end()
+                .ends_with("async");
+            FN_TRAITS
+                .iter()
+                .filter(|t| **t != old && !(already_async && t.starts_with("async")))
+                .map(|t| {
+                    (
+                        format!("{}{}", t, rest),
+                        format!("{} -> {}", old, t),
+                        if t.starts_with("async") {
+                            "async_trait_bounds"
+                        } else {
+                            ""
+                        },
+                    )
+                })
+                .collect()
+        }
+        _ => capture_replacements(source_code, node),
+    }
+}
+
+pub fn mutate_closures(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_gates(source_code, structs, mutation_count, closures_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_closures(source_code, &nodes, 0)
+    }
+
+    // async closure는 stable이라 gate를 넣으면 warning만 늘어난다
+    #[test]
+    fn async_closures_are_not_gated() {
+        let source_code = "fn f(g: impl Fn(u8)) { let h = |x: u8| x; }";
+        let all = mutants(source_code);
+        assert!(all.contains(&"fn f(g: impl Fn(u8)) { let h = async |x: u8| x; }".to_string()));
+        assert!(all.contains(&"fn f(g: impl AsyncFn(u8)) { let h = |x: u8| x; }".to_string()));
+        assert!(all.iter().all(|m| !m.contains("async_closure")));
+        assert!(all.contains(
+            &"#![feature(async_trait_bounds)]\nfn f(g: impl async Fn(u8)) { let h = |x: u8| x; }"
+                .to_string()
+        ));
+    }
+}
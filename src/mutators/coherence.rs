@@ -0,0 +1,513 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_gates, node_text, Replacement};
+use crate::TypePosInfo;
+
+// trait/impl coherence와 specialization을 노리는 item 단위 mutator.
+// TypePosInfo 목록은 평평해서 impl 하나가 어떤 generics, trait, type, body로 되어 있는지 모른다.
+// 여기서는 impl_item과 trait_item node를 다시 찾아서 각 부분으로 나눈 다음 다시 조립한다.
+// 조금 다른 generics로 복제한 impl, blanket impl, default item, negative impl,
+// inherent impl <-> trait impl, 기본값 있는 associated item, 순환 supertrait, unsafe/auto trait 을 만든다.
+
+// trait body 안에 넣는 associated item들
+const ASSOCIATED_ITEMS: [(&str, &str); 6] = [
+    ("type __Assoc = ();", "associated_type_defaults"),
+    ("type __Assoc: Clone = Self;", "associated_type_defaults"),
+    ("const __C: usize = 0;", ""),
+    (
+        "const __C: usize = Self::__D; const __D: usize = Self::__C;",
+        "",
+    ),
+    ("const __C: Self;", ""),
+    ("fn __f(&self) -> Self where Self: Sized { loop {} }", ""),
+];
+
+// impl 하나를 이루는 부분들
+#[derive(Clone)]
+struct Impl {
+    unsafety: bool,
+    // <T: Copy> 처럼 꺾쇠까지 포함한다
+    generics: String,
+    negative: bool,
+    trait_name: Option<String>,
+    ty: String,
+    where_clause: String,
+    body: String,
+}
+
+impl Impl {
+    fn parse(source_code: &str, node: Node) -> Option<Impl> {
+        let text = |n: Option<Node>| n.map(|n| node_text(source_code, n).to_string());
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        Some(Impl {
+            unsafety: children.iter().any(|c| c.kind() == "unsafe"),
+            generics: text(node.child_by_field_name("type_parameters")).unwrap_or_default(),
+            negative: children.iter().any(|c| c.kind() == "!"),
+            trait_name: text(node.child_by_field_name("trait")),
+            ty: text(node.child_by_field_name("type"))?,
+            where_clause: text(
+                children
+                    .iter()
+                    .find(|c| c.kind() == "where_clause")
+                    .copied(),
+            )
+            .map(|w| format!("{} ", w))
+            .unwrap_or_default(),
+            body: text(node.child_by_field_name("body"))?,
+        })
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "{}impl{} {}{} {}{}",
+            if self.unsafety { "unsafe " } else { "" },
+            self.generics,
+            match &self.trait_name {
+                Some(t) => format!("{}{} for ", if self.negative { "!" } else { "" }, t),
+                None => String::new(),
+            },
+            self.ty,
+            self.where_clause,
+            self.body
+        )
+    }
+}
+
+// <T: Copy> 에 parameter를 하나 더한다. 비어 있으면 <param>
+fn add_parameter(generics: &str, param: &str) -> String {
+    match generics.strip_suffix('>') {
+        Some(open) => format!("{}, {}>", open, param),
+        None => format!("<{}>", param),
+    }
+}
+
+// 식별자 단위로만 바꾼다. (T 를 바꿀 때 Tr 은 그대로)
+fn replace_word(text: &str, old: &str, new: &str) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || c == '_' || c == '\'' {
+            word.push(c);
+            continue;
+        }
+        out.push_str(if word == old { new } else { &word });
+        word.clear();
+        out.push(c);
+    }
+    out.pop();
+    out
+}
+
+// type_parameters 에서 선언한 이름과, 그 자리에 넣을 구체적인 값
+fn parameter_names(source_code: &str, node: Node) -> Vec<(String, &'static str)> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return vec![];
+    };
+    let mut names = vec![];
+    let mut cursor = params.walk();
+    for param in params.named_children(&mut cursor) {
+        let (name, concrete) = match param.kind() {
+            "type_identifier" => (Some(param), "()"),
+            "lifetime" => (Some(param), "'static"),
+            "constrained_type_parameter" => match param.child_by_field_name("left") {
+                Some(left) if left.kind() == "lifetime" => (Some(left), "'static"),
+                left => (left, "()"),
+            },
+            "optional_type_parameter" => (param.child_by_field_name("name"), "()"),
+            "const_parameter" => (param.child_by_field_name("name"), "0"),
+            _ => (None, ""),
+        };
+        if let Some(name) = name {
+            names.push((node_text(source_code, name).to_string(), concrete));
+        }
+    }
+    names
+}
+
+// body 안의 item 앞에 default 를 붙인다. pub 이 있으면 그 뒤에.
+fn default_items(source_code: &str, body: Node) -> String {
+    let mut out = String::new();
+    let mut last = body.start_byte();
+    let mut cursor = body.walk();
+    for item in body.named_children(&mut cursor) {
+        if !matches!(item.kind(), "function_item" | "const_item" | "type_item") {
+            continue;
+        }
+        let mut item_cursor = item.walk();
+        let at = item
+            .children(&mut item_cursor)
+            .find(|c| c.kind() == "visibility_modifier")
+            .map(|v| v.end_byte() + 1)
+            .unwrap_or(item.start_byte());
+        out.push_str(&source_code[last..at]);
+        out.push_str("default ");
+        last = at;
+    }
+    out.push_str(&source_code[last..body.end_byte()]);
+    out
+}
+
+// inherent impl의 fn과 const로 trait 선언을 만든다
+fn trait_declarations(source_code: &str, body: Node) -> Vec<String> {
+    let mut declarations = vec![];
+    let mut cursor = body.walk();
+    for item in body.named_children(&mut cursor) {
+        let end = match item.kind() {
+            "function_item" => item.child_by_field_name("body"),
+            "const_item" => item.child_by_field_name("value"),
+            _ => None,
+        };
+        let Some(end) = end else {
+            continue;
+        };
+        let mut item_cursor = item.walk();
+        let start = item
+            .children(&mut item_cursor)
+            .find(|c| c.kind() == "visibility_modifier")
+            .map(|v| v.end_byte())
+            .unwrap_or(item.start_byte());
+        let declaration = source_code[start..end.start_byte()].trim();
+        let declaration = declaration.strip_suffix('=').unwrap_or(declaration).trim();
+        declarations.push(format!("{};", declaration));
+    }
+    declarations
+}
+
+fn impl_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let (Some(original), Some(body)) = (
+        Impl::parse(source_code, node),
+        node.child_by_field_name("body"),
+    ) else {
+        return exprs;
+    };
+    let text = node_text(source_code, node);
+    let mut add = |new: String, note: &str, gate: &'static str| {
+        exprs.push((format!("{}\n{}", text, new), note.to_string(), gate));
+    };
+
+    // 겹치는 impl
+    add(text.to_string(), "duplicate impl", "");
+    let mut with_where = original.clone();
+    with_where.where_clause = if with_where.where_clause.is_empty() {
+        "where Self: 'static ".to_string()
+    } else {
+        format!("{}, Self: 'static ", with_where.where_clause.trim_end())
+    };
+    add(with_where.render(), "duplicate impl with where", "");
+    let params = parameter_names(source_code, node);
+    if !params.is_empty() {
+        let mut concrete = original.clone();
+        concrete.generics = String::new();
+        // where 절은 구체적인 type에 대해서는 의미가 없으니 뺀다
+        concrete.where_clause = String::new();
+        for (name, value) in params.iter() {
+            concrete.trait_name = concrete.trait_name.map(|t| replace_word(&t, name, value));
+            concrete.ty = replace_word(&concrete.ty, name, value);
+        }
+        add(
+            concrete.render(),
+            "duplicate impl with concrete generics",
+            "",
+        );
+    }
+    if original.trait_name.is_some() {
+        let mut blanket = original.clone();
+        blanket.generics = add_parameter(&original.generics, "__T");
+        blanket.ty = "__T".to_string();
+        blanket.where_clause = String::new();
+        add(blanket.render(), "blanket impl", "");
+        blanket.generics = add_parameter(&original.generics, "__T: ?Sized");
+        blanket.body = "{}".to_string();
+        add(blanket.render(), "empty blanket impl", "");
+
+        // specialization
+        blanket.generics = add_parameter(&original.generics, "__T");
+        blanket.body = default_items(source_code, body);
+        add(
+            blanket.render(),
+            "blanket impl with default items",
+            "specialization",
+        );
+        let mut defaulted = original.clone();
+        defaulted.body = default_items(source_code, body);
+        exprs.push((
+            defaulted.render(),
+            "default items".to_string(),
+            "specialization",
+        ));
+        exprs.push((
+            format!("default {}", original.render()),
+            "default impl".to_string(),
+            "specialization",
+        ));
+
+        // negative impl
+        let mut negative = original.clone();
+        negative.negative = !original.negative;
+        negative.body = "{}".to_string();
+        let gate = if negative.negative {
+            "negative_impls"
+        } else {
+            ""
+        };
+        exprs.push((negative.render(), "toggle negative impl".to_string(), gate));
+        exprs.push((
+            format!("{}\n{}", text, negative.render()),
+            "add opposite impl".to_string(),
+            "negative_impls",
+        ));
+
+        // trait impl -> inherent impl
+        let mut inherent = original.clone();
+        inherent.trait_name = None;
+        inherent.negative = false;
+        exprs.push((
+            inherent.render(),
+            "trait impl -> inherent impl".to_string(),
+            "",
+        ));
+    } else {
+        // inherent impl -> trait impl. 선언에 impl의 generic parameter가 나올 수 있으니 trait도 같이 받는다
+        let declarations = trait_declarations(source_code, body);
+        let names: Vec<String> = params.into_iter().map(|(n, _)| n).collect();
+        let mut trait_impl = original.clone();
+        trait_impl.trait_name = Some(if names.is_empty() {
+            "__Inherent".to_string()
+        } else {
+            format!("__Inherent<{}>", names.join(", "))
+        });
+        trait_impl.body = original
+            .body
+            .replace("pub fn", "fn")
+            .replace("pub const", "const");
+        exprs.push((
+            format!(
+                "trait __Inherent{} {{ {} }}\n{}",
+                original.generics,
+                declarations.join(" "),
+                trait_impl.render()
+            ),
+            "inherent impl -> trait impl".to_string(),
+            "",
+        ));
+    }
+
+    let mut unsafety = original.clone();
+    unsafety.unsafety = !original.unsafety;
+    exprs.push((unsafety.render(), "toggle unsafe impl".to_string(), ""));
+    exprs
+}
+
+fn trait_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let (Some(name), Some(body)) = (
+        node.child_by_field_name("name"),
+        node.child_by_field_name("body"),
+    ) else {
+        return exprs;
+    };
+    let name = node_text(source_code, name);
+    let text = node_text(source_code, node);
+    let insert_at = |at: usize, new: &str| {
+        format!(
+            "{}{}{}",
+            &source_code[node.start_byte()..at],
+            new,
+            &source_code[at..node.end_byte()]
+        )
+    };
+
+    // 기본값 있는 associated item
+    for (item, gate) in ASSOCIATED_ITEMS {
+        exprs.push((
+            insert_at(body.start_byte() + 1, &format!(" {}", item)),
+            format!("add {}", item),
+            gate,
+        ));
+    }
+    let mut cursor = body.walk();
+    for item in body.named_children(&mut cursor) {
+        let item_text = node_text(source_code, item);
+        if item.kind() == "associated_type" && !item_text.contains('=') {
+            let default = format!("{} = Self;", item_text.trim_end_matches(';'));
+            exprs.push((
+                format!(
+                    "{}{}{}",
+                    &source_code[node.start_byte()..item.start_byte()],
+                    default,
+                    &source_code[item.end_byte()..node.end_byte()]
+                ),
+                "add associated type default".to_string(),
+                "associated_type_defaults",
+            ));
+        }
+    }
+
+    // 순환하는 supertrait
+    let params: Vec<String> = parameter_names(source_code, node)
+        .into_iter()
+        .map(|(n, _)| n)
+        .collect();
+    let this = if params.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}<{}>", name, params.join(", "))
+    };
+    let supertrait = |bound: &str| match node.child_by_field_name("bounds") {
+        Some(bounds) => insert_at(bounds.end_byte(), &format!(" + {}", bound)),
+        None => {
+            let after = node
+                .child_by_field_name("type_parameters")
+                .map(|p| p.end_byte())
+                .unwrap_or(node.child_by_field_name("name").unwrap().end_byte());
+            insert_at(after, &format!(": {}", bound))
+        }
+    };
+    exprs.push((supertrait(&this), "supertrait itself".to_string(), ""));
+    exprs.push((
+        format!("{}\ntrait __Cycle: {} {{}}", supertrait("__Cycle"), name),
+        "supertrait cycle".to_string(),
+        "",
+    ));
+    let mut cursor = node.walk();
+    let where_clause = node
+        .children(&mut cursor)
+        .find(|c| c.kind() == "where_clause");
+    exprs.push((
+        match where_clause {
+            Some(w) => insert_at(w.end_byte(), &format!(", Self: {}", this)),
+            None => insert_at(body.start_byte(), &format!("where Self: {} ", this)),
+        },
+        "where Self: itself".to_string(),
+        "",
+    ));
+
+    // unsafe / auto trait
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    if let Some(trait_keyword) = children.iter().find(|c| c.kind() == "trait") {
+        let at = trait_keyword.start_byte();
+        match children.iter().find(|c| c.kind() == "unsafe") {
+            Some(unsafe_keyword) => exprs.push((
+                format!(
+                    "{}{}",
+                    &source_code[node.start_byte()..unsafe_keyword.start_byte()],
+                    source_code[unsafe_keyword.end_byte()..node.end_byte()].trim_start()
+                ),
+                "remove unsafe".to_string(),
+                "",
+            )),
+            None => {
+                exprs.push((insert_at(at, "unsafe "), "add unsafe".to_string(), ""));
+                exprs.push((
+                    insert_at(at, "unsafe auto "),
+                    "add unsafe auto".to_string(),
+                    "auto_traits",
+                ));
+            }
+        }
+        if !text.contains("auto trait") {
+            exprs.push((
+                insert_at(at, "auto "),
+                "add auto".to_string(),
+                "auto_traits",
+            ));
+        }
+    }
+    exprs.push((
+        insert_at(node.start_byte(), "#[marker]\n"),
+        "add #[marker]".to_string(),
+        "marker_trait_attr",
+    ));
+    exprs
+}
+
+fn coherence_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    match node.kind() {
+        "impl_item" => impl_replacements(source_code, node),
+        "trait_item" => trait_replacements(source_code, node),
+        _ => vec![],
+    }
+}
+
+pub fn mutate_coherence(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_gates(source_code, structs, mutation_count, coherence_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_coherence(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn words_and_parameters() {
+        assert_eq!(
+            replace_word("Tr<T, 'a> for &'a T", "T", "()"),
+            "Tr<(), 'a> for &'a ()"
+        );
+        assert_eq!(replace_word("&'a T", "'a", "'static"), "&'static T");
+        assert_eq!(add_parameter("<T: Copy>", "__T"), "<T: Copy, __T>");
+        assert_eq!(add_parameter("", "__T"), "<__T>");
+    }
+
+    #[test]
+    fn trait_impls_overlap_and_specialize() {
+        let source_code = "impl<T: Copy> Tr for Vec<T> { fn f(&self) {} }";
+        assert_mutants(
+            source_code,
+            &[
+                "impl<T: Copy> Tr for Vec<T> { fn f(&self) {} }\nimpl Tr for Vec<()> { fn f(&self) {} }",
+                "impl<T: Copy> Tr for Vec<T> { fn f(&self) {} }\nimpl<T: Copy, __T> Tr for __T { fn f(&self) {} }",
+                "#![feature(specialization)]\nimpl<T: Copy> Tr for Vec<T> { default fn f(&self) {} }",
+                "#![feature(negative_impls)]\nimpl<T: Copy> !Tr for Vec<T> {}",
+                "impl<T: Copy> Vec<T> { fn f(&self) {} }",
+                "unsafe impl<T: Copy> Tr for Vec<T> { fn f(&self) {} }",
+            ],
+        );
+    }
+
+    #[test]
+    fn inherent_impls_become_trait_impls() {
+        assert_mutants(
+            "impl<T> S<T> { pub fn f(&self) -> T { todo!() } pub const C: u8 = 1; }",
+            &["trait __Inherent<T> { fn f(&self) -> T; const C: u8; }\nimpl<T> __Inherent<T> for S<T> { fn f(&self) -> T { todo!() } const C: u8 = 1; }"],
+        );
+    }
+
+    #[test]
+    fn traits_get_defaults_cycles_and_markers() {
+        assert_mutants(
+            "trait A<T> { type X; }",
+            &[
+                "#![feature(associated_type_defaults)]\ntrait A<T> { type X = Self; }",
+                "trait A<T>: A<T> { type X; }",
+                "trait A<T>: __Cycle { type X; }\ntrait __Cycle: A {}",
+                "trait A<T> where Self: A<T> { type X; }",
+                "#![feature(auto_traits)]\nunsafe auto trait A<T> { type X; }",
+                "#![feature(marker_trait_attr)]\n#[marker]\ntrait A<T> { type X; }",
+            ],
+        );
+        assert_mutants(
+            "unsafe trait B: Copy {}",
+            &["trait B: Copy {}", "unsafe trait B: Copy + B {}"],
+        );
+    }
+}
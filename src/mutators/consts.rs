@@ -0,0 +1,368 @@
+use tree_sitter::Node;
+
+use super::{find_node, mutate_with_gates, node_text, parse, Replacement};
+use crate::TypePosInfo;
+
+// const generics와 const-eval을 노리는 mutator.
+// const_item, static_item, array_type 의 길이, const_parameter, type_parameters 를 기준으로
+// type parameter를 const parameter로 바꾸고, 배열 길이를 generic expression으로 바꾸고,
+// loop, panic, raw pointer 연산, transmute 가 들어간 const 본문을 넣는다.
+
+// usize 값이 되는 const 본문들
+const CONST_BODIES: [&str; 12] = [
+    "{ let mut i = 0; while i < 10 { i += 1; } i }",
+    "{ let mut i = 0usize; loop { i += 1; if i > 100 { break i; } } }",
+    "{ let mut n = 0usize; let mut i = 0; while i < 10_000_000 { n = n.wrapping_add(i); i += 1; } n }",
+    "{ panic!(\"const panic\") }",
+    "{ let a = [1usize, 2, 3]; a[3] }",
+    "{ let x = 0usize; x - 1 }",
+    "unsafe { *(&[1usize, 2] as *const usize).add(1) }",
+    "unsafe { *(&[1usize, 2] as *const usize).add(2) }",
+    "unsafe { std::mem::transmute::<[u8; 8], usize>([1; 8]) }",
+    "unsafe { std::mem::transmute::<&u8, usize>(&0u8) }",
+    "{ const fn f(n: usize) -> usize { if n == 0 { 0 } else { f(n - 1) + 1 } } f(100) }",
+    "{ const fn f(n: usize) -> usize { f(n + 1) } f(0) }",
+];
+
+// 배열 길이에 넣을 generic expression. {} 자리에 const parameter 이름이 들어간다
+const GENERIC_LENGTHS: [&str; 5] = [
+    "{ {} + 1 }",
+    "{ {} * {} }",
+    "{ {} - 1 }",
+    "{ [0u8; {}].len() }",
+    "{ if {} > 0 { {} } else { 1 } }",
+];
+
+const CONST_PARAM_TYPES: [(&str, &str); 6] = [
+    ("usize", ""),
+    ("u8", ""),
+    ("bool", ""),
+    ("char", ""),
+    ("&'static str", "adt_const_params"),
+    ("[usize; 2]", "adt_const_params"),
+];
+
+// inline const와 const/static item으로 옮길 expression들
+const MOVABLE_EXPRESSIONS: [&str; 6] = [
+    "binary_expression",
+    "call_expression",
+    "array_expression",
+    "unary_expression",
+    "reference_expression",
+    "integer_literal",
+];
+
+// 같은 파일의 const parameter 이름과 const item 이름
+#[derive(Default)]
+struct Names {
+    const_params: Vec<String>,
+    const_items: Vec<String>,
+}
+
+fn collect_names(source_code: &str, structs: &[TypePosInfo]) -> Names {
+    let tree = parse(source_code);
+    let mut names = Names::default();
+    for info in structs.iter() {
+        let Some(node) = find_node(&tree, info) else {
+            continue;
+        };
+        let list = match node.kind() {
+            "const_parameter" => &mut names.const_params,
+            "const_item" => &mut names.const_items,
+            _ => continue,
+        };
+        if let Some(name) = node.child_by_field_name("name") {
+            let name = node_text(source_code, name).to_string();
+            if !list.contains(&name) {
+                list.push(name);
+            }
+        }
+    }
+    if names.const_params.is_empty() {
+        names.const_params.push("N".to_string());
+    }
+    names
+}
+
+fn length_replacements(names: &Names) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    for name in names.const_params.iter() {
+        exprs.push((name.clone(), format!("length {}", name), ""));
+        for length in GENERIC_LENGTHS {
+            let length = length.replace("{}", name);
+            exprs.push((length.clone(), length, "generic_const_exprs"));
+        }
+    }
+    for name in names.const_items.iter() {
+        exprs.push((name.clone(), format!("length {}", name), ""));
+    }
+    for body in CONST_BODIES {
+        exprs.push((body.to_string(), "const body".to_string(), ""));
+    }
+    exprs.push(("usize::MAX".to_string(), "usize::MAX".to_string(), ""));
+    exprs
+}
+
+// [T; 3] 과 [x; 3] 의 길이만 바꾼다
+fn array_replacements(source_code: &str, node: Node, names: &Names) -> Vec<Replacement> {
+    let Some(length) = node.child_by_field_name("length") else {
+        return vec![];
+    };
+    length_replacements(names)
+        .into_iter()
+        .map(|(new, note, gate)| {
+            (
+                format!(
+                    "{}{}{}",
+                    &source_code[node.start_byte()..length.start_byte()],
+                    new,
+                    &source_code[length.end_byte()..node.end_byte()]
+                ),
+                note,
+                gate,
+            )
+        })
+        .collect()
+}
+
+// <T, U: Clone> 의 type parameter 하나를 const parameter로
+fn type_parameters_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let mut cursor = node.walk();
+    for param in node.named_children(&mut cursor) {
+        let name = match param.kind() {
+            "type_identifier" => param,
+            "constrained_type_parameter" => match param.child_by_field_name("left") {
+                Some(left) if left.kind() == "type_identifier" => left,
+                _ => continue,
+            },
+            "optional_type_parameter" => match param.child_by_field_name("name") {
+                Some(name) => name,
+                None => continue,
+            },
+            _ => continue,
+        };
+        let name = node_text(source_code, name);
+        for (ty, gate) in CONST_PARAM_TYPES {
+            exprs.push((
+                format!(
+                    "{}const {}: {}{}",
+                    &source_code[node.start_byte()..param.start_byte()],
+                    name,
+                    ty,
+                    &source_code[param.end_byte()..node.end_byte()]
+                ),
+                format!("{} -> const {}: {}", name, name, ty),
+                gate,
+            ));
+        }
+    }
+    exprs
+}
+
+fn const_parameter_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let (Some(name), Some(ty)) = (
+        node.child_by_field_name("name"),
+        node.child_by_field_name("type"),
+    ) else {
+        return exprs;
+    };
+    let name = node_text(source_code, name);
+    let ty = node_text(source_code, ty);
+    for (new_ty, gate) in CONST_PARAM_TYPES {
+        if new_ty != ty {
+            exprs.push((
+                format!("const {}: {}", name, new_ty),
+                format!("{} -> {}", ty, new_ty),
+                gate,
+            ));
+        }
+    }
+    exprs.push((
+        format!("const {}: {} = {{ {} + 1 }}", name, ty, name),
+        "default refers to itself".to_string(),
+        "",
+    ));
+    exprs.push((
+        format!("const {}: [usize; {}]", name, name),
+        "type refers to itself".to_string(),
+        "adt_const_params",
+    ));
+    exprs
+}
+
+// const X: T = v; 의 값을 바꾸거나, const fn 으로 빼거나, static 으로 바꾼다
+fn item_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let mut exprs = vec![];
+    let (Some(name), Some(ty), Some(value)) = (
+        node.child_by_field_name("name"),
+        node.child_by_field_name("type"),
+        node.child_by_field_name("value"),
+    ) else {
+        return exprs;
+    };
+    let head = &source_code[node.start_byte()..value.start_byte()];
+    let tail = &source_code[value.end_byte()..node.end_byte()];
+    let ty = node_text(source_code, ty);
+    for body in CONST_BODIES {
+        exprs.push((
+            format!("{}({}) as {}{}", head, body, ty, tail),
+            "const body".to_string(),
+            "",
+        ));
+    }
+    let name = node_text(source_code, name);
+    let const_fn = format!("__{}_fn", name.to_lowercase());
+    for body in CONST_BODIES.iter().take(4) {
+        exprs.push((
+            format!(
+                "{}{}() as {}{}\nconst fn {}() -> usize {}",
+                head, const_fn, ty, tail, const_fn, body
+            ),
+            "move into const fn".to_string(),
+            "",
+        ));
+    }
+    let text = node_text(source_code, node);
+    if node.kind() == "const_item" {
+        exprs.push((
+            text.replacen("const", "static", 1),
+            "const -> static".to_string(),
+            "",
+        ));
+    } else {
+        exprs.push((
+            text.replacen("static", "const", 1),
+            "static -> const".to_string(),
+            "",
+        ));
+    }
+    exprs
+}
+
+fn consts_replacements(source_code: &str, node: Node, names: &Names) -> Vec<Replacement> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "array_type" | "array_expression" => {
+            let mut exprs = array_replacements(source_code, node, names);
+            if node.kind() == "array_expression" {
+                exprs.push((
+                    format!("const {{ {} }}", text),
+                    "inline const".to_string(),
+                    "",
+                ));
+            }
+            exprs
+        }
+        "type_parameters" => type_parameters_replacements(source_code, node),
+        "const_parameter" => const_parameter_replacements(source_code, node),
+        "const_item" | "static_item" => item_replacements(source_code, node),
+        "function_item" => {
+            // fn 을 const fn 으로
+            let mut cursor = node.walk();
+            let fn_keyword = node.children(&mut cursor).find(|c| c.kind() == "fn");
+            match fn_keyword {
+                Some(fn_keyword) if !text.contains("const fn") => vec![(
+                    format!(
+                        "{}const {}",
+                        &source_code[node.start_byte()..fn_keyword.start_byte()],
+                        &source_code[fn_keyword.start_byte()..node.end_byte()]
+                    ),
+                    "fn -> const fn".to_string(),
+                    "",
+                )],
+                _ => vec![],
+            }
+        }
+        kind if MOVABLE_EXPRESSIONS.contains(&kind) => vec![
+            (
+                format!("const {{ {} }}", text),
+                "inline const".to_string(),
+                "",
+            ),
+            (
+                format!("{{ const C: _ = {}; C }}", text),
+                "move into const item".to_string(),
+                "",
+            ),
+            (
+                format!("{{ static S: _ = {}; &S }}", text),
+                "move into static item".to_string(),
+                "",
+            ),
+        ],
+        _ => vec![],
+    }
+}
+
+pub fn mutate_consts(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    let names = collect_names(source_code, structs);
+    mutate_with_gates(source_code, structs, mutation_count, |source_code, node| {
+        consts_replacements(source_code, node, &names)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_consts(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn array_lengths_use_const_params_with_gates() {
+        assert_mutants(
+            "struct S<const M: usize>([u8; 3]);",
+            &[
+                "struct S<const M: usize>([u8; M]);",
+                "#![feature(generic_const_exprs)]\nstruct S<const M: usize>([u8; { M + 1 }]);",
+                "struct S<const M: usize>([u8; usize::MAX]);",
+            ],
+        );
+        // const parameter가 없으면 N을 쓴다
+        assert_mutants("fn f() { [0; 3]; }", &["fn f() { [0; N]; }"]);
+    }
+
+    #[test]
+    fn type_and_const_parameters_change() {
+        assert_mutants(
+            "struct S<T: Clone, const N: usize>(T);",
+            &[
+                "struct S<const T: bool, const N: usize>(T);",
+                "#![feature(adt_const_params)]\nstruct S<const T: &'static str, const N: usize>(T);",
+                "struct S<T: Clone, const N: char>(T);",
+                "struct S<T: Clone, const N: usize = { N + 1 }>(T);",
+            ],
+        );
+    }
+
+    #[test]
+    fn items_and_functions_become_const_eval() {
+        assert_mutants(
+            "const A: usize = 1;\nfn f() -> usize { A }",
+            &[
+                "static A: usize = 1;\nfn f() -> usize { A }",
+                "const A: usize = ({ panic!(\"const panic\") }) as usize;\nfn f() -> usize { A }",
+                "const A: usize = __a_fn() as usize;\nconst fn __a_fn() -> usize { panic!(\"const panic\") }\nfn f() -> usize { A }",
+                "const A: usize = const { 1 };\nfn f() -> usize { A }",
+                "const A: usize = 1;\nconst fn f() -> usize { A }",
+            ],
+        );
+        assert_mutants("static B: u8 = 2;", &["const B: u8 = 2;"]);
+    }
+}
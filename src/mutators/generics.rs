@@ -0,0 +1,302 @@
+use tree_sitter::Node;
+
+use super::{find_node, mutate_with_notes, node_text, parse};
+use crate::TypePosInfo;
+
+// lifetime과 generic parameter, bound를 건드리는 mutator.
+// trait과 lifetime 관련 버그가 tests/fixed 의 큰 비중을 차지하는데, 지금까지는 이 node들을
+// 지우거나 통째로 바꾸는 것밖에 못했다. 여기서는 parameter와 bound를 하나씩 더하고 빼고 복제한다.
+// 새로 넣는 parameter, bound, predicate는 같은 파일의 node collection에서 가져온다.
+
+const LIFETIMES: [&str; 3] = ["'static", "'_", "'z"];
+
+const EXTRA_PARAMS: [&str; 5] = ["'z", "Z", "Z: ?Sized", "const N: usize", "Z: 'static"];
+
+const EXTRA_BOUNDS: [&str; 8] = [
+    "?Sized",
+    "~const Clone",
+    "use<>",
+    "'static",
+    "Send",
+    "for<'z> Fn(&'z ())",
+    "Sized",
+    "Copy",
+];
+
+const EXTRA_PREDICATES: [&str; 5] = [
+    "Self: Sized",
+    "for<'z> &'z Self: Clone",
+    "'static: 'static",
+    "(): Copy",
+    "i32: Iterator",
+];
+
+// 같은 파일에서 모은 재료들
+#[derive(Default)]
+struct Donors {
+    lifetimes: Vec<String>,
+    params: Vec<String>,
+    bounds: Vec<String>,
+    predicates: Vec<String>,
+}
+
+fn push_unique(list: &mut Vec<String>, text: &str) {
+    if !list.iter().any(|t| t == text) {
+        list.push(text.to_string());
+    }
+}
+
+fn named_children<'t>(node: Node<'t>) -> Vec<Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|c| !c.kind().ends_with("comment"))
+        .collect()
+}
+
+fn collect_donors(source_code: &str, structs: &[TypePosInfo]) -> Donors {
+    let tree = parse(source_code);
+    let mut donors = Donors::default();
+    for info in structs.iter() {
+        let Some(node) = find_node(&tree, info) else {
+            continue;
+        };
+        match node.kind() {
+            "lifetime" => push_unique(&mut donors.lifetimes, node_text(source_code, node)),
+            "type_parameters" => {
+                for c in named_children(node) {
+                    push_unique(&mut donors.params, node_text(source_code, c));
+                }
+            }
+            "trait_bounds" => {
+                for c in named_children(node) {
+                    push_unique(&mut donors.bounds, node_text(source_code, c));
+                }
+            }
+            "where_clause" => {
+                for c in named_children(node) {
+                    push_unique(&mut donors.predicates, node_text(source_code, c));
+                }
+            }
+            _ => {}
+        }
+    }
+    donors
+}
+
+// 목록 하나를 지우기/복제하기/더하기로 바꾼 모든 경우.
+// render는 바뀐 목록으로 node 전체의 코드를 다시 만든다.
+fn list_edits<R>(items: &[String], extra: &[String], what: &str, render: R) -> Vec<(String, String)>
+where
+    R: Fn(&[String]) -> String,
+{
+    let mut exprs = vec![];
+    for i in 0..items.len() {
+        let mut removed = items.to_vec();
+        let item = removed.remove(i);
+        exprs.push((render(&removed), format!("remove {} {}", what, item)));
+
+        let mut duplicated = items.to_vec();
+        duplicated.insert(i, items[i].clone());
+        exprs.push((
+            render(&duplicated),
+            format!("duplicate {} {}", what, items[i]),
+        ));
+    }
+    for e in extra.iter() {
+        let mut added = items.to_vec();
+        added.push(e.clone());
+        exprs.push((render(&added), format!("add {} {}", what, e)));
+    }
+    exprs
+}
+
+fn texts(source_code: &str, nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|n| node_text(source_code, *n).to_string())
+        .collect()
+}
+
+fn extras(builtin: &[&str], donors: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = builtin.iter().map(|s| s.to_string()).collect();
+    for d in donors.iter() {
+        push_unique(&mut out, d);
+    }
+    out
+}
+
+fn generics_replacements(source_code: &str, node: Node, donors: &Donors) -> Vec<(String, String)> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "lifetime" => {
+            let mut exprs = vec![];
+            for l in LIFETIMES
+                .iter()
+                .map(|s| s.to_string())
+                .chain(donors.lifetimes.iter().cloned())
+            {
+                exprs.push((l.clone(), format!("{} -> {}", text, l)));
+            }
+            exprs
+        }
+        "type_parameters" => {
+            let params = named_children(node);
+            let items = texts(source_code, &params);
+            let mut exprs = list_edits(
+                &items,
+                &extras(&EXTRA_PARAMS, &donors.params),
+                "param",
+                |p| format!("<{}>", p.join(", ")),
+            );
+            // type parameter에 bound 붙이기
+            for (i, param) in params.iter().enumerate() {
+                if param.kind() != "type_identifier" {
+                    continue;
+                }
+                for bound in ["?Sized", "'static", "~const Clone", "for<'z> Fn(&'z Self)"] {
+                    let mut bounded = items.clone();
+                    bounded[i] = format!("{}: {}", items[i], bound);
+                    exprs.push((
+                        format!("<{}>", bounded.join(", ")),
+                        format!("bound {}: {}", items[i], bound),
+                    ));
+                }
+            }
+            exprs
+        }
+        "trait_bounds" => {
+            let bounds = named_children(node);
+            let items = texts(source_code, &bounds);
+            let mut exprs = list_edits(
+                &items,
+                &extras(&EXTRA_BOUNDS, &donors.bounds),
+                "bound",
+                |b| format!(": {}", b.join(" + ")),
+            );
+            // trait bound 앞에 for<'z> binder 넣기
+            for (i, bound) in bounds.iter().enumerate() {
+                if bound.kind() == "lifetime" || bound.kind() == "higher_ranked_trait_bound" {
+                    continue;
+                }
+                let mut binder = items.clone();
+                binder[i] = format!("for<'z> {}", items[i]);
+                exprs.push((
+                    format!(": {}", binder.join(" + ")),
+                    format!("add for<'z> to {}", items[i]),
+                ));
+            }
+            exprs
+        }
+        "where_clause" => {
+            let predicates = named_children(node);
+            let items = texts(source_code, &predicates);
+            list_edits(
+                &items,
+                &extras(&EXTRA_PREDICATES, &donors.predicates),
+                "predicate",
+                |p| format!("where {}", p.join(", ")),
+            )
+        }
+        "higher_ranked_trait_bound" => {
+            let mut exprs = vec![];
+            if let (Some(params), Some(bound)) = (
+                node.child_by_field_name("type_parameters"),
+                node.child_by_field_name("type"),
+            ) {
+                let bound = node_text(source_code, bound);
+                let params = node_text(source_code, params);
+                exprs.push((bound.to_string(), "remove for<..>".to_string()));
+                for binder in ["<>", "<'z>", "<'a, 'z>", "<T>", "<const N: usize>"] {
+                    exprs.push((
+                        format!("for{} {}", binder, bound),
+                        format!("for{} -> for{}", params, binder),
+                    ));
+                }
+                exprs.push((format!("for<'z> {}", text), "nest for<'z>".to_string()));
+            }
+            exprs
+        }
+        // fn(&u8) 같은 함수 포인터 타입에 binder 넣기
+        "function_type" => vec![(format!("for<'z> {}", text), "add for<'z>".to_string())],
+        // impl Trait 에 precise capturing, lifetime, ?Sized bound 더하기
+        "abstract_type" => ["use<>", "use<'z>", "'static", "?Sized", "Send"]
+            .iter()
+            .map(|b| (format!("{} + {}", text, b), format!("add bound {}", b)))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+pub fn mutate_generics(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    let donors = collect_donors(source_code, structs);
+    mutate_with_notes(source_code, structs, mutation_count, |source_code, node| {
+        generics_replacements(source_code, node, &donors)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_generics(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn type_parameters_are_removed_duplicated_added_and_bounded() {
+        assert_mutants(
+            "fn f<'a, T>(x: &'a T) {}",
+            &[
+                "fn f<T>(x: &'a T) {}",
+                "fn f<'a, 'a, T>(x: &'a T) {}",
+                "fn f<'a, T, const N: usize>(x: &'a T) {}",
+                "fn f<'a, T: ?Sized>(x: &'a T) {}",
+                "fn f<'a, T>(x: &'static T) {}",
+            ],
+        );
+    }
+
+    #[test]
+    fn bounds_and_predicates_use_donors_from_the_file() {
+        assert_mutants(
+            "fn f<T: Clone>() where T: Default {} fn g<U: Ord>() {}",
+            &[
+                "fn f<T: Clone + Ord>() where T: Default {} fn g<U: Ord>() {}",
+                "fn f<T: for<'z> Clone>() where T: Default {} fn g<U: Ord>() {}",
+                "fn f<T: Clone>() where T: Default, Self: Sized {} fn g<U: Ord>() {}",
+                "fn f<T: Clone>() where T: Default {} fn g<U: Ord + Clone>() {}",
+            ],
+        );
+    }
+
+    #[test]
+    fn binders_are_added_and_changed() {
+        assert_mutants(
+            "fn f<F>() where F: for<'a> Fn(&'a u8) {}",
+            &[
+                "fn f<F>() where F: Fn(&'a u8) {}",
+                "fn f<F>() where F: for<'a, 'z> Fn(&'a u8) {}",
+            ],
+        );
+        assert_mutants(
+            "fn f(g: fn(&u8)) -> impl Sized {}",
+            &[
+                "fn f(g: for<'z> fn(&u8)) -> impl Sized {}",
+                "fn f(g: fn(&u8)) -> impl Sized + use<> {}",
+            ],
+        );
+    }
+}
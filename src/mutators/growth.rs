@@ -0,0 +1,225 @@
+use tree_sitter::Node;
+
+use super::{is_type, mutate_with_notes, node_text};
+use crate::TypePosInfo;
+
+// 프로그램을 키우는 mutator.
+// stack overflow나 지수적으로 느려지는 버그(issue-72933-match-stack-overflow.rs,
+// issue-74564-if-expr-stack-overflow.rs)는 반복과 중첩에서 나오는데, 다른 mutator는 코드를 줄이거나 바꾸기만 한다.
+// 여기서는 node를 형제들 사이에 N번 복제하고, expression과 type을 자기 자신 안에 k번 중첩한다.
+
+// 복제 횟수와 중첩 깊이의 상한. --max-copies, --max-depth 로 정한다.
+// 큰 item을 수천 번 복제하면 mutant 하나가 수십 MB가 되니, 바꾼 코드의 크기도 max_bytes로 자른다.
+pub struct Growth {
+    pub max_copies: usize,
+    pub max_depth: usize,
+    pub max_bytes: usize,
+}
+
+// 이 node들의 자식은 형제들 사이에 복제해도 문법이 깨지지 않는다. (부모 kind, 구분자)
+const CONTAINERS: [(&str, &str); 16] = [
+    ("source_file", "\n"),
+    ("declaration_list", "\n"),
+    ("block", "\n"),
+    ("match_block", "\n"),
+    ("field_declaration_list", ", "),
+    ("enum_variant_list", ", "),
+    ("field_initializer_list", ", "),
+    ("arguments", ", "),
+    ("parameters", ", "),
+    ("array_expression", ", "),
+    ("tuple_expression", ", "),
+    ("type_arguments", ", "),
+    ("type_parameters", ", "),
+    ("tuple_type", ", "),
+    ("tuple_pattern", ", "),
+    ("trait_bounds", " + "),
+];
+
+// type을 감싸는 방법. {} 자리에 원래 type이 들어간다
+const TYPE_WRAPPERS: [&str; 5] = ["Option<{}>", "Box<{}>", "&{}", "[{}; 1]", "({},)"];
+
+// expression을 감싸는 방법
+const EXPR_WRAPPERS: [&str; 3] = ["({})", "{ {} }", "(|| {})()"];
+
+// 2, 16, 128, ... 처럼 8배씩 키우다가 max에서 멈춘다
+fn levels(max: usize) -> Vec<usize> {
+    let mut out = vec![];
+    let mut n = 2;
+    while n < max {
+        out.push(n);
+        n *= 8;
+    }
+    if max >= 2 {
+        out.push(max);
+    }
+    out
+}
+
+fn nest(wrapper: &str, text: &str, depth: usize) -> String {
+    let (open, close) = wrapper.split_once("{}").unwrap();
+    format!("{}{}{}", open.repeat(depth), text, close.repeat(depth))
+}
+
+fn duplicate_among_siblings(
+    source_code: &str,
+    node: Node,
+    growth: &Growth,
+) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let Some(sep) = node.parent().and_then(|p| {
+        CONTAINERS
+            .iter()
+            .find(|(kind, _)| *kind == p.kind())
+            .map(|(_, sep)| *sep)
+    }) else {
+        return exprs;
+    };
+    if !node.is_named() || node.kind().ends_with("comment") {
+        return exprs;
+    }
+    let mut text = node_text(source_code, node).to_string();
+    // match arm은 ,나 } 로 끝나야 뒤에 다른 arm이 올 수 있다
+    if node.kind() == "match_arm" && !text.ends_with(',') && !text.ends_with('}') {
+        text.push(',');
+    }
+    for n in levels(growth.max_copies) {
+        if text.len() * n > growth.max_bytes {
+            break;
+        }
+        exprs.push((vec![text.as_str(); n].join(sep), format!("copy x{}", n)));
+    }
+    exprs
+}
+
+fn nest_replacements(source_code: &str, node: Node, growth: &Growth) -> Vec<(String, String)> {
+    let text = node_text(source_code, node);
+    let mut exprs = vec![];
+    let depths = levels(growth.max_depth);
+    if is_type(node) {
+        for wrapper in TYPE_WRAPPERS {
+            for &k in depths.iter() {
+                exprs.push((nest(wrapper, text, k), format!("nest {} x{}", wrapper, k)));
+            }
+        }
+    }
+    match node.kind() {
+        "if_expression" => {
+            // if if if c {true} else {false} ... { }
+            if let Some(condition) = node.child_by_field_name("condition") {
+                let c = node_text(source_code, condition);
+                for &k in depths.iter() {
+                    let nested = format!(
+                        "{}{}{}",
+                        "if ".repeat(k),
+                        c,
+                        " { true } else { false }".repeat(k)
+                    );
+                    exprs.push((
+                        format!(
+                            "{}{}{}",
+                            &source_code[node.start_byte()..condition.start_byte()],
+                            nested,
+                            &source_code[condition.end_byte()..node.end_byte()]
+                        ),
+                        format!("nest condition x{}", k),
+                    ));
+                }
+            }
+            // if c {a} else if c {a} else if ... else {b}
+            if let Some(alternative) = node.child_by_field_name("alternative") {
+                let head = &source_code[node.start_byte()..alternative.start_byte()];
+                let tail = node_text(source_code, alternative);
+                for &k in depths.iter() {
+                    exprs.push((
+                        format!("{}{}{}", head, format!("else {}", head).repeat(k), tail),
+                        format!("else if x{}", k),
+                    ));
+                }
+            }
+        }
+        "match_expression" => {
+            // match x { _ => match x { _ => ... } }
+            if let Some(value) = node.child_by_field_name("value") {
+                let v = node_text(source_code, value);
+                for &k in depths.iter() {
+                    exprs.push((
+                        nest(&format!("match {} {{ _ => {{}} }}", v), text, k),
+                        format!("nest match x{}", k),
+                    ));
+                }
+            }
+        }
+        "binary_expression" => {
+            // a + b -> a + b + b + ... 왼쪽으로 깊어지는 tree
+            if let (Some(operator), Some(right)) = (
+                node.child_by_field_name("operator"),
+                node.child_by_field_name("right"),
+            ) {
+                let tail = format!(
+                    " {} {}",
+                    node_text(source_code, operator),
+                    node_text(source_code, right)
+                );
+                for &k in depths.iter() {
+                    exprs.push((
+                        format!("{}{}", text, tail.repeat(k)),
+                        format!("chain x{}", k),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+    if node.kind().ends_with("_expression") {
+        for wrapper in EXPR_WRAPPERS {
+            for &k in depths.iter() {
+                exprs.push((nest(wrapper, text, k), format!("nest {} x{}", wrapper, k)));
+            }
+        }
+    }
+    exprs
+}
+
+pub fn mutate_growth(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    growth: &Growth,
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_notes(source_code, structs, mutation_count, |source_code, node| {
+        let mut exprs = duplicate_among_siblings(source_code, node, growth);
+        exprs.append(&mut nest_replacements(source_code, node, growth));
+        exprs.retain(|(n, _)| n.len() <= growth.max_bytes);
+        exprs
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutators::parse;
+
+    #[test]
+    fn levels_grow_by_eight_up_to_max() {
+        assert_eq!(levels(256), [2, 16, 128, 256]);
+        assert_eq!(levels(2), [2]);
+        assert!(levels(1).is_empty());
+    }
+
+    #[test]
+    fn duplicates_stop_at_max_bytes() {
+        let source_code = "fn f() { g(); }";
+        let tree = parse(source_code);
+        let call = tree.root_node().descendant_for_byte_range(9, 13).unwrap();
+        assert_eq!(call.kind(), "expression_statement");
+        let growth = Growth {
+            max_copies: 256,
+            max_depth: 256,
+            max_bytes: 100,
+        };
+        let exprs = duplicate_among_siblings(source_code, call, &growth);
+        assert_eq!(exprs.len(), 2);
+        assert_eq!(exprs[0].0, "g();\ng();");
+    }
+}
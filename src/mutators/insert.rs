@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use tree_sitter::Node;
+
+use super::{find_node, parse};
+use crate::TypePosInfo;
+
+// donor pool에서 문장과 item을 가져와 끼워 넣는 mutator.
+// splice는 있던 node를 바꾸기만 해서 함수 본문에 문장이 늘거나 module에 item이 늘지 않는다.
+// 여기서는 block, declaration_list, source_file 의 자식 사이에 donor 조각을 새로 넣는다.
+
+const ITEM_KINDS: [&str; 4] = ["impl_item", "trait_item", "function_item", "mod_item"];
+
+const STATEMENT_KINDS: [&str; 6] = [
+    "let_declaration",
+    "expression_statement",
+    "impl_item",
+    "trait_item",
+    "function_item",
+    "mod_item",
+];
+
+// 이 container 안에 넣을 수 있는 donor 종류
+fn insertable_kinds(container: Node) -> &'static [&'static str] {
+    match container.kind() {
+        "block" => &STATEMENT_KINDS,
+        "source_file" => &ITEM_KINDS,
+        "declaration_list" => match container.parent().map(|p| p.kind()) {
+            // impl과 trait 안에는 함수만 넣는다
+            Some("impl_item") | Some("trait_item") => &ITEM_KINDS[2..3],
+            _ => &ITEM_KINDS,
+        },
+        _ => &[],
+    }
+}
+
+// ; 로 끝났거나 item이라 뒤에 다른 문장이 와도 되는 node.
+// x, vec![..], unsafe { .. } 처럼 이게 아닌 건 전부 block의 tail expression이다.
+// tree-sitter는 ; 없는 unsafe { .. }, { .. } 도 expression_statement로 읽으니 ; 까지 본다
+fn is_statement(node: Node) -> bool {
+    let kind = node.kind();
+    if kind == "expression_statement" {
+        return node
+            .child(node.child_count().saturating_sub(1))
+            .map(|c| c.kind())
+            == Some(";");
+    }
+    kind.ends_with("_statement")
+        || kind.ends_with("_item")
+        || kind == "let_declaration"
+        || kind == "macro_definition"
+        || kind == ";"
+}
+
+// 자식 사이의 넣을 수 있는 위치들.
+// block의 마지막 expression(;없는 tail) 뒤에는 넣으면 안 되니 그 앞까지만 쓴다.
+fn insertion_points(source_code: &str, container: Node) -> Vec<usize> {
+    let mut cursor = container.walk();
+    let children: Vec<Node> = container
+        .named_children(&mut cursor)
+        .filter(|c| !c.kind().ends_with("comment"))
+        .collect();
+    let mut points: Vec<usize> = children.iter().map(|c| c.start_byte()).collect();
+    let end = if container.kind() == "source_file" {
+        source_code.len()
+    } else {
+        // 닫는 } 바로 앞
+        container.end_byte().saturating_sub(1)
+    };
+    let tail_is_expression =
+        container.kind() == "block" && children.last().map(|c| !is_statement(*c)).unwrap_or(false);
+    if !tail_is_expression {
+        points.push(end);
+    }
+    points
+}
+
+pub fn mutate_insert(
+    source_code: &str,
+    new_expressions: &HashMap<&str, Vec<String>>,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    if mutation_count == 0 {
+        panic!("Stopped because there might be too much mutated files.");
+    }
+    let tree = parse(source_code);
+
+    // (container, 넣을 위치, 넣을 수 있는 donor 종류). source_file은 structs에 없어서 따로 넣는다
+    let mut containers: Vec<(Node, Vec<usize>, Vec<&str>)> = vec![];
+    let root = tree.root_node();
+    let mut nodes = vec![root];
+    nodes.extend(structs.iter().filter_map(|info| find_node(&tree, info)));
+    for node in nodes {
+        let kinds: Vec<&str> = insertable_kinds(node)
+            .iter()
+            .copied()
+            .filter(|k| {
+                new_expressions
+                    .get(k)
+                    .map(|exprs| exprs.iter().any(|e| !e.is_empty()))
+                    .unwrap_or(false)
+            })
+            .collect();
+        if !kinds.is_empty() {
+            containers.push((node, insertion_points(source_code, node), kinds));
+        }
+    }
+
+    let mut modified_versions = Vec::new();
+    let mut index = 0;
+    let mut rng = rand::thread_rng();
+    // splice와 같이, 100번 시도해도 하나도 못 만들면 포기한다
+    let mut check_zero_mutation = 0;
+    while index < mutation_count {
+        check_zero_mutation += 1;
+        if check_zero_mutation > 100 && index == 0 {
+            break;
+        }
+        let Some((container, points, kinds)) = containers.choose(&mut rng) else {
+            break;
+        };
+        let (Some(&at), Some(kind)) = (points.choose(&mut rng), kinds.choose(&mut rng)) else {
+            continue;
+        };
+        let Some(donor) = new_expressions[kind].choose(&mut rng) else {
+            continue;
+        };
+        if donor.is_empty() {
+            continue;
+        }
+        modified_versions.push(format!(
+            "{}\n{}\n{}",
+            &source_code[..at],
+            donor,
+            &source_code[at..]
+        ));
+        index += 1;
+        println!(
+            "[{}] {}-{} {} (insert {} at byte {}) :  -> {}",
+            index,
+            container.start_position(),
+            container.end_position(),
+            container.kind(),
+            kind,
+            at,
+            donor
+        );
+    }
+    modified_versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // source_code의 첫 fn 본문 block에 넣을 수 있는 위치
+    fn block_points(source_code: &str) -> Vec<usize> {
+        let tree = parse(source_code);
+        let function = tree.root_node().named_child(0).unwrap();
+        let body = function.child_by_field_name("body").unwrap();
+        insertion_points(source_code, body)
+    }
+
+    #[test]
+    fn no_insertion_after_tail_identifier() {
+        let source_code = "fn f() -> u8 { let x = 1; x }";
+        assert_eq!(block_points(source_code), [15, 26]);
+    }
+
+    #[test]
+    fn no_insertion_after_tail_macro_or_block() {
+        assert_eq!(block_points("fn f() -> Vec<u8> { vec![1] }"), [20]);
+        assert_eq!(block_points("fn f() -> u8 { unsafe { 1 } }"), [15]);
+        assert_eq!(block_points("fn f() -> u8 { { 1 } }"), [15]);
+    }
+
+    #[test]
+    fn insertion_after_last_statement() {
+        let source_code = "fn f() { g(); let y = 2; }";
+        assert_eq!(block_points(source_code), [9, 14, 25]);
+    }
+}
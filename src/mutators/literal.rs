@@ -0,0 +1,284 @@
+use tree_sitter::Node;
+
+use super::{mutate_with, node_text};
+use crate::TypePosInfo;
+
+// literal을 경계값으로 바꾸는 mutator.
+// const-eval과 overflow lint 쪽에서 ICE가 많이 나오니, 타입의 끝 값, 하나 넘친 값,
+// suffix 바꾸기, 거대한 지수, NaN 같은 값 위주로 사전을 만든다.
+
+const INT_SUFFIXES: [&str; 12] = [
+    "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "usize", "isize",
+];
+
+const INT_DICT: [&str; 20] = [
+    "0",
+    "1",
+    "-1",
+    "u128::MAX",
+    "i128::MIN",
+    "i64::MIN",
+    "u64::MAX",
+    "usize::MAX",
+    "isize::MIN",
+    "i32::MAX",
+    // i32::MAX + 1
+    "2147483648",
+    // u64::MAX + 1
+    "18446744073709551616",
+    // u128::MAX, u128::MAX + 1
+    "340282366920938463463374607431768211455",
+    "340282366920938463463374607431768211456",
+    "0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF",
+    "0b1111_1111",
+    "0o777",
+    "1_000_000_000_000_000_000",
+    "(-128i8)",
+    "(1 << 127)",
+];
+
+const FLOAT_DICT: [&str; 16] = [
+    "0.0",
+    "-0.0",
+    "1e308",
+    "1e309",
+    "1e-400",
+    "1e99999999999",
+    "1.7976931348623157e308",
+    "f64::NAN",
+    "f32::NAN",
+    "f64::INFINITY",
+    "f64::NEG_INFINITY",
+    "f32::MIN_POSITIVE",
+    "f64::EPSILON",
+    "(0.0 / 0.0)",
+    "0.1f32",
+    "1f64",
+];
+
+const CHAR_DICT: [&str; 10] = [
+    r"'\0'",
+    r"'\u{10FFFF}'",
+    r"'\u{FFFF}'",
+    r"'\u{D800}'",
+    r"'\x7f'",
+    r"'\''",
+    r"'\\'",
+    "'ß'",
+    "'🦀'",
+    "'\u{301}'",
+];
+
+const STRING_CONTENTS: [&str; 12] = [
+    "",
+    r"\0",
+    r"\u{10FFFF}",
+    r"\u{0}\u{7f}",
+    r"\x00\x7f",
+    r"\\",
+    r#"\""#,
+    "{}",
+    "{0} {x} {:?}",
+    "{{}}",
+    "\\\n    continued",
+    "🦀\u{202e}",
+];
+
+// raw string의 # 개수. 255개가 최대, 256개는 에러.
+const RAW_HASHES: [usize; 5] = [0, 1, 5, 255, 256];
+
+const FLOAT_SUFFIXES: [&str; 4] = ["f32", "f64", "f16", "f128"];
+
+fn split_int_suffix(text: &str) -> (&str, &str) {
+    for suffix in INT_SUFFIXES.iter().chain(["f32", "f64"].iter()) {
+        if let Some(value) = text.strip_suffix(suffix) {
+            // 0x1f32 같은 16진수는 f32 suffix가 아니다
+            if !(suffix.starts_with('f') && value.starts_with("0x")) {
+                return (value, suffix);
+            }
+        }
+    }
+    (text, "")
+}
+
+fn parse_int(value: &str) -> Option<u128> {
+    let value = value.replace('_', "");
+    if let Some(hex) = value.strip_prefix("0x") {
+        u128::from_str_radix(hex, 16).ok()
+    } else if let Some(oct) = value.strip_prefix("0o") {
+        u128::from_str_radix(oct, 8).ok()
+    } else if let Some(bin) = value.strip_prefix("0b") {
+        u128::from_str_radix(bin, 2).ok()
+    } else {
+        value.parse::<u128>().ok()
+    }
+}
+
+// 해당 suffix 타입의 최댓값 + 1. 딱 한 칸 넘치는 값.
+fn overflow_by_one(suffix: &str) -> Option<String> {
+    let bits: u32 = match suffix {
+        "u8" | "i8" => 8,
+        "u16" | "i16" => 16,
+        "u32" | "i32" | "" => 32,
+        "u64" | "i64" | "usize" | "isize" => 64,
+        "u128" | "i128" => 128,
+        _ => return None,
+    };
+    let unsigned = suffix.starts_with('u');
+    let max_plus_one: u128 = match (unsigned, bits) {
+        (true, 128) => return Some("340282366920938463463374607431768211456u128".to_string()),
+        (true, b) => 1u128 << b,
+        (false, b) => 1u128 << (b - 1),
+    };
+    Some(format!("{}{}", max_plus_one, suffix))
+}
+
+fn integer_replacements(text: &str) -> Vec<String> {
+    let (value, suffix) = split_int_suffix(text);
+    let mut exprs: Vec<String> = INT_DICT.iter().map(|s| s.to_string()).collect();
+    // suffix 바꾸기: 1u8 -> 1i128 / 1usize / 1f32
+    for new_suffix in INT_SUFFIXES.iter().chain(["f32", "f64", ""].iter()) {
+        exprs.push(format!("{}{}", value, new_suffix));
+    }
+    if let Some(v) = parse_int(value) {
+        if let Some(next) = v.checked_add(1) {
+            exprs.push(format!("{}{}", next, suffix));
+        }
+        exprs.push(format!("{:#x}{}", v, suffix));
+    }
+    if let Some(overflow) = overflow_by_one(suffix) {
+        exprs.push(overflow);
+    }
+    exprs
+}
+
+fn float_replacements(text: &str) -> Vec<String> {
+    let value = FLOAT_SUFFIXES
+        .iter()
+        .find_map(|s| text.strip_suffix(s))
+        .unwrap_or(text);
+    // 1. 뒤에 바로 suffix나 지수를 붙이면 1.f32 처럼 field 접근이 되니 . 을 뗀다
+    let value = value.trim_end_matches('.');
+    let mut exprs: Vec<String> = FLOAT_DICT.iter().map(|s| s.to_string()).collect();
+    for suffix in FLOAT_SUFFIXES {
+        exprs.push(format!("{}{}", value, suffix));
+    }
+    // 지수 키우기. 이미 있는 지수는 떼고 붙인다 (1.5e3 -> 1.5e308)
+    let mantissa = value.split(['e', 'E']).next().unwrap_or(value);
+    let mantissa = mantissa.trim_end_matches('.');
+    exprs.push(format!("{}e308", mantissa));
+    exprs.push(format!("{}e-330", mantissa));
+    exprs
+}
+
+// b"..", c"..", r#".."# 의 prefix와 내용을 나눈다.
+fn split_string(text: &str) -> (&str, &str) {
+    let prefix_end = text.find(['"', '#']).unwrap_or(0);
+    let prefix = &text[..prefix_end];
+    let body = text[prefix_end..].trim_matches('#');
+    let content = body
+        .strip_prefix('"')
+        .and_then(|b| b.strip_suffix('"'))
+        .unwrap_or(body);
+    (prefix.trim_end_matches('r'), content)
+}
+
+fn raw_string(prefix: &str, content: &str, hashes: usize) -> String {
+    let h = "#".repeat(hashes);
+    format!("{}r{}\"{}\"{}", prefix, h, content, h)
+}
+
+fn string_replacements(text: &str, raw: bool) -> Vec<String> {
+    let (prefix, content) = split_string(text);
+    let mut exprs = vec![];
+    for c in STRING_CONTENTS.iter() {
+        exprs.push(format!("{}\"{}\"", prefix, c));
+    }
+    exprs.push(format!("\"{}\"", "a".repeat(5_000)));
+    // byte string, C string 으로 바꾸기
+    for new_prefix in ["b", "c", ""] {
+        exprs.push(format!("{}\"{}\"", new_prefix, content));
+    }
+    // raw string 으로 바꾸기. 내용에 "# 이 들어가서 #이 많아야 하는 경우도 만든다
+    for hashes in RAW_HASHES {
+        exprs.push(raw_string(prefix, content, hashes));
+    }
+    exprs.push(raw_string(prefix, "\"#\"##\"###", 4));
+    if raw {
+        // raw string -> 일반 string
+        exprs.push(format!(
+            "{}\"{}\"",
+            prefix,
+            content.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+    }
+    exprs
+}
+
+pub fn literal_replacements(source_code: &str, node: Node) -> Vec<String> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "integer_literal" => integer_replacements(text),
+        "float_literal" => float_replacements(text),
+        "char_literal" => CHAR_DICT.iter().map(|s| s.to_string()).collect(),
+        "string_literal" => string_replacements(text, false),
+        "raw_string_literal" => string_replacements(text, true),
+        _ => vec![],
+    }
+}
+
+pub fn mutate_literal(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with(source_code, structs, mutation_count, literal_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // dictionary를 뺀, 원래 literal에서 만든 대체 코드
+    fn derived(text: &str) -> Vec<String> {
+        float_replacements(text).split_off(FLOAT_DICT.len())
+    }
+
+    #[test]
+    fn float_exponent_is_replaced() {
+        assert_eq!(
+            derived("1.5e3"),
+            [
+                "1.5e3f32",
+                "1.5e3f64",
+                "1.5e3f16",
+                "1.5e3f128",
+                "1.5e308",
+                "1.5e-330"
+            ]
+        );
+        assert_eq!(derived("2E-7")[4..], ["2e308", "2e-330"]);
+    }
+
+    #[test]
+    fn float_suffix_and_trailing_dot_are_removed() {
+        assert_eq!(
+            derived("1.0f16"),
+            ["1.0f32", "1.0f64", "1.0f16", "1.0f128", "1.0e308", "1.0e-330"]
+        );
+        assert_eq!(
+            derived("3."),
+            ["3f32", "3f64", "3f16", "3f128", "3e308", "3e-330"]
+        );
+        assert_eq!(derived("4.e2f64")[4..], ["4e308", "4e-330"]);
+    }
+
+    #[test]
+    fn integer_overflow_by_one() {
+        assert_eq!(overflow_by_one("u8").as_deref(), Some("256u8"));
+        assert_eq!(overflow_by_one("i8").as_deref(), Some("128i8"));
+        assert_eq!(overflow_by_one("f32"), None);
+        assert_eq!(split_int_suffix("0x1f32"), ("0x1f32", ""));
+        assert_eq!(parse_int("0b1_01"), Some(5));
+    }
+}
@@ -0,0 +1,322 @@
+use rand::seq::SliceRandom;
+use tree_sitter::Node;
+
+use super::{find_node, mutate_with_notes, node_text, parse};
+use crate::TypePosInfo;
+
+// macro_rules! 와 macro invocation 안쪽을 건드리는 mutator.
+// tree-sitter는 macro 본문을 token_tree로 읽어서, 지금까지는 통째로 splice 되기만 했다.
+// 여기서는 fragment specifier, 반복 연산자와 separator를 바꾸고,
+// arm 복제, 자기 자신을 다시 부르는 arm, 다른 macro의 token tree 끼워 넣기를 한다.
+
+const FRAGMENT_SPECIFIERS: [&str; 15] = [
+    "block",
+    "expr",
+    "expr_2021",
+    "ident",
+    "item",
+    "lifetime",
+    "literal",
+    "meta",
+    "pat",
+    "pat_param",
+    "path",
+    "stmt",
+    "tt",
+    "ty",
+    "vis",
+];
+
+const REPETITION_OPS: [&str; 3] = ["*", "+", "?"];
+
+const SEPARATORS: [&str; 5] = ["", ",", ";", "=>", "|"];
+
+// 같은 파일의 token tree를 선언 하나당 이만큼까지만 가져다 쓴다
+const DONORS_PER_NODE: usize = 8;
+
+// 같은 파일에서 모은 macro 재료들
+#[derive(Default)]
+struct Donors {
+    // macro invocation의 인자와 macro arm의 오른쪽
+    token_trees: Vec<String>,
+    // macro arm의 왼쪽
+    patterns: Vec<String>,
+}
+
+fn push_unique(list: &mut Vec<String>, text: &str) {
+    if !list.iter().any(|t| t == text) {
+        list.push(text.to_string());
+    }
+}
+
+fn collect_donors(source_code: &str, structs: &[TypePosInfo]) -> Donors {
+    let tree = parse(source_code);
+    let mut donors = Donors::default();
+    for info in structs.iter() {
+        let Some(node) = find_node(&tree, info) else {
+            continue;
+        };
+        match node.kind() {
+            "token_tree" if is_macro_body(node) => {
+                push_unique(&mut donors.token_trees, node_text(source_code, node))
+            }
+            "token_tree_pattern" if node.parent().map(|p| p.kind()) == Some("macro_rule") => {
+                push_unique(&mut donors.patterns, node_text(source_code, node))
+            }
+            _ => {}
+        }
+    }
+    donors
+}
+
+// macro invocation의 인자나 macro arm의 오른쪽처럼, 바깥쪽 token tree인지
+fn is_macro_body(node: Node) -> bool {
+    matches!(
+        node.parent().map(|p| p.kind()),
+        Some("macro_invocation") | Some("macro_rule")
+    )
+}
+
+fn sample(list: &[String], except: &str) -> Vec<String> {
+    let mut out: Vec<String> = list.iter().filter(|t| *t != except).cloned().collect();
+    out.shuffle(&mut rand::thread_rng());
+    out.truncate(DONORS_PER_NODE);
+    out
+}
+
+// $( ... ) sep op 를 (안쪽, separator, 연산자) 로 나눈다.
+fn split_repetition<'s>(source_code: &'s str, node: Node) -> Option<(&'s str, &'s str, &'s str)> {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    let open = children.iter().find(|c| c.kind() == "(")?;
+    let close = children.iter().rev().find(|c| c.kind() == ")")?;
+    let op = children.last()?;
+    Some((
+        &source_code[open.end_byte()..close.start_byte()],
+        source_code[close.end_byte()..op.start_byte()].trim(),
+        node_text(source_code, *op),
+    ))
+}
+
+fn repetition_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let Some((inner, sep, op)) = split_repetition(source_code, node) else {
+        return exprs;
+    };
+    for new_op in REPETITION_OPS {
+        if new_op != op {
+            exprs.push((
+                format!("$({}){}{}", inner, sep, new_op),
+                format!("{} -> {}", op, new_op),
+            ));
+        }
+    }
+    for new_sep in SEPARATORS {
+        if new_sep != sep {
+            exprs.push((
+                format!("$({}){}{}", inner, new_sep, op),
+                format!("separator {:?} -> {:?}", sep, new_sep),
+            ));
+        }
+    }
+    // 반복 풀기, 한 번 더 감싸기
+    exprs.push((inner.to_string(), "remove repetition".to_string()));
+    exprs.push((
+        format!("$({}){}{}", node_text(source_code, node), sep, op),
+        "nest repetition".to_string(),
+    ));
+    exprs
+}
+
+// arm 왼쪽의 $x:frag 를 $x 로 바꿔서, 같은 arm에 다시 맞는 invocation 인자를 만든다.
+fn pattern_to_args(source_code: &str, pattern: Node) -> String {
+    let mut bindings = vec![];
+    let mut stack = vec![pattern];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "token_binding_pattern" {
+            bindings.push(node);
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    bindings.sort_by_key(|n| n.start_byte());
+
+    let mut out = String::new();
+    let mut last = pattern.start_byte();
+    for b in bindings {
+        out.push_str(&source_code[last..b.start_byte()]);
+        if let Some(name) = b.child_by_field_name("name") {
+            out.push_str(node_text(source_code, name));
+        }
+        last = b.end_byte();
+    }
+    out.push_str(&source_code[last..pattern.end_byte()]);
+    out
+}
+
+fn macro_rule_replacements(
+    source_code: &str,
+    node: Node,
+    donors: &Donors,
+) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let text = node_text(source_code, node);
+    let (Some(left), Some(right)) = (
+        node.child_by_field_name("left"),
+        node.child_by_field_name("right"),
+    ) else {
+        return exprs;
+    };
+    // arm 복제. 같은 arm이 두 번 있으면 두 번째는 안 쓰인다
+    exprs.push((format!("{}; {}", text, text), "duplicate arm".to_string()));
+
+    // 자기 자신을 다시 부르는 arm
+    let name = node
+        .parent()
+        .and_then(|d| d.child_by_field_name("name"))
+        .map(|n| node_text(source_code, n));
+    if let Some(name) = name {
+        let left_text = node_text(source_code, left);
+        let right_text = node_text(source_code, right);
+        let args = pattern_to_args(source_code, left);
+        let (open, body) = right_text.split_at(1);
+        for (recursion, note) in [
+            (
+                format!("{}{}!{}; {}", open, name, args, body),
+                "recurse first",
+            ),
+            (
+                format!("{}{}!{}{}", open, name, args, &body[body.len() - 1..]),
+                "recurse only",
+            ),
+            (format!("{}{}!(); {}", open, name, body), "recurse empty"),
+        ] {
+            exprs.push((format!("{} => {}", left_text, recursion), note.to_string()));
+        }
+        // 같은 macro의 맨 앞에 재귀 arm을 하나 더 두기
+        exprs.push((
+            format!("{} => {{ {}!{} }}; {}", left_text, name, args, text),
+            "add recursive arm".to_string(),
+        ));
+    }
+    // 다른 arm의 왼쪽 pattern으로 바꾸기
+    for pattern in sample(&donors.patterns, node_text(source_code, left)) {
+        exprs.push((
+            format!("{} => {}", pattern, node_text(source_code, right)),
+            "splice pattern".to_string(),
+        ));
+    }
+    exprs
+}
+
+fn token_tree_replacements(
+    source_code: &str,
+    node: Node,
+    donors: &Donors,
+) -> Vec<(String, String)> {
+    let text = node_text(source_code, node);
+    let mut exprs = vec![];
+    if text.len() >= 2 {
+        let (open, rest) = text.split_at(1);
+        let (inner, close) = rest.split_at(rest.len() - 1);
+        exprs.push((
+            format!("{}{} {}{}", open, inner, inner, close),
+            "duplicate tokens".to_string(),
+        ));
+        exprs.push((format!("{}{}{}", open, text, close), "nest".to_string()));
+        exprs.push((format!("{}{}", open, close), "empty".to_string()));
+    }
+    // 다른 macro의 token tree를 가져오기
+    for tree in sample(&donors.token_trees, text) {
+        exprs.push((tree, "splice token tree".to_string()));
+    }
+    exprs
+}
+
+fn macro_replacements(source_code: &str, node: Node, donors: &Donors) -> Vec<(String, String)> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "fragment_specifier" => FRAGMENT_SPECIFIERS
+            .iter()
+            .filter(|f| **f != text)
+            .map(|f| (f.to_string(), format!("{} -> {}", text, f)))
+            .collect(),
+        "token_repetition_pattern" | "token_repetition" => {
+            repetition_replacements(source_code, node)
+        }
+        "macro_rule" => macro_rule_replacements(source_code, node, donors),
+        "token_tree" if is_macro_body(node) => token_tree_replacements(source_code, node, donors),
+        _ => vec![],
+    }
+}
+
+pub fn mutate_macro(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    let donors = collect_donors(source_code, structs);
+    mutate_with_notes(source_code, structs, mutation_count, |source_code, node| {
+        macro_replacements(source_code, node, &donors)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    const MACRO: &str = "macro_rules! m { ($($x:expr),*) => { 0 $(+ $x)* }; }";
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_macro(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn fragments_and_repetitions_change() {
+        assert_mutants(
+            MACRO,
+            &[
+                "macro_rules! m { ($($x:tt),*) => { 0 $(+ $x)* }; }",
+                "macro_rules! m { ($($x:expr);*) => { 0 $(+ $x)* }; }",
+                "macro_rules! m { ($($x:expr),+) => { 0 $(+ $x)* }; }",
+                "macro_rules! m { ($x:expr) => { 0 $(+ $x)* }; }",
+                "macro_rules! m { ($($x:expr),*) => { 0 $($(+ $x)*)* }; }",
+            ],
+        );
+    }
+
+    #[test]
+    fn arms_are_duplicated_and_made_recursive() {
+        assert_mutants(
+            MACRO,
+            &[
+                "macro_rules! m { ($($x:expr),*) => { 0 $(+ $x)* }; ($($x:expr),*) => { 0 $(+ $x)* }; }",
+                "macro_rules! m { ($($x:expr),*) => {m!($($x),*);  0 $(+ $x)* }; }",
+                "macro_rules! m { ($($x:expr),*) => {m!($($x),*)}; }",
+            ],
+        );
+    }
+
+    #[test]
+    fn invocation_token_trees_are_spliced() {
+        assert_mutants(
+            "fn main() { println!(\"{}\", 1); vec![2, 3]; }",
+            &[
+                "fn main() { println!(\"{}\", 1 \"{}\", 1); vec![2, 3]; }",
+                "fn main() { println!(\"{}\", 1); vec![]; }",
+                "fn main() { println!(\"{}\", 1); vec![[2, 3]]; }",
+                "fn main() { println!(\"{}\", 1); vec!(\"{}\", 1); }",
+            ],
+        );
+    }
+}
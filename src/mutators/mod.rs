@@ -0,0 +1,300 @@
+pub mod attribute;
+pub mod closures;
+pub mod coherence;
+pub mod consts;
+pub mod generics;
+pub mod growth;
+pub mod insert;
+pub mod literal;
+pub mod macros;
+pub mod operator;
+pub mod patterns;
+pub mod rename;
+pub mod types;
+pub mod unsafety;
+pub mod wrap;
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use tree_sitter::{Node, Tree};
+
+use crate::TypePosInfo;
+
+// 이름 붙은 mutation profile. --mode 대신 --profile 로 고르면
+// mode 하나와 oracle이 rustc에 더 넘길 인자를 같이 켠다.
+pub struct Profile {
+    pub name: &'static str,
+    pub mode: i32,
+    pub rustc_args: &'static [&'static str],
+}
+
+pub const PROFILES: [Profile; 1] = [Profile {
+    name: "unsafe",
+    mode: 20,
+    rustc_args: &["-Zmir-opt-level=4", "-Zvalidate-mir", "-Copt-level=3"],
+}];
+
+pub fn profile(name: &str) -> &'static Profile {
+    PROFILES
+        .iter()
+        .find(|p| p.name == name)
+        .unwrap_or_else(|| panic!("No such profile: {}", name))
+}
+
+// 여기 있는 mutator들은 전부 "node 하나를 다른 코드로 바꾼다" 형태다.
+// 각 mutator는 node 하나를 받아 가능한 대체 코드 목록만 돌려주고,
+// 어떤 node를 고를지, 몇 개를 만들지는 mutate_with가 mutate_self와 같은 방식으로 처리한다.
+
+pub fn parse(source_code: &str) -> Tree {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_rust::language()).unwrap();
+    parser.parse(source_code, None).unwrap()
+}
+
+// TypePosInfo는 위치만 들고 있어서, parent나 자식을 보려면 tree에서 node를 다시 찾아야 한다.
+// 같은 범위에 node가 여러 개 겹칠 수 있으니 kind까지 맞는 걸 찾는다.
+pub fn find_node<'t>(tree: &'t Tree, info: &TypePosInfo) -> Option<Node<'t>> {
+    let &(type_string, start_byte, end_byte, _, _) = info;
+    let mut node = tree
+        .root_node()
+        .descendant_for_byte_range(start_byte, end_byte)?;
+    loop {
+        if node.kind() == type_string
+            && node.start_byte() == start_byte
+            && node.end_byte() == end_byte
+        {
+            return Some(node);
+        }
+        node = node.parent()?;
+        if node.start_byte() != start_byte || node.end_byte() != end_byte {
+            return None;
+        }
+    }
+}
+
+pub fn node_text<'s>(source_code: &'s str, node: Node) -> &'s str {
+    &source_code[node.start_byte()..node.end_byte()]
+}
+
+// grammar의 _type 에 속하는 node인지. struct S 의 S, <T> 의 T 처럼 이름을 선언하는 자리와
+// Vec<u8> 의 Vec 처럼 generic type의 이름 부분은 뺀다.
+pub fn is_type(node: Node) -> bool {
+    match node.kind() {
+        "abstract_type"
+        | "array_type"
+        | "bounded_type"
+        | "dynamic_type"
+        | "function_type"
+        | "generic_type"
+        | "never_type"
+        | "pointer_type"
+        | "primitive_type"
+        | "reference_type"
+        | "scoped_type_identifier"
+        | "tuple_type"
+        | "unit_type" => true,
+        "type_identifier" => match node.parent() {
+            Some(parent) => {
+                parent.child_by_field_name("name") != Some(node)
+                    && !matches!(
+                        parent.kind(),
+                        "type_parameters" | "constrained_type_parameter" | "generic_type"
+                    )
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+// replacements가 돌려준 대체 코드로 mutant를 만든다.
+// mutation_count가 0이면 가능한 모든 mutant를, 아니면 mutation_count개를 랜덤으로 만든다.
+pub fn mutate_with<F>(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+    replacements: F,
+) -> Vec<String>
+where
+    F: Fn(&str, Node) -> Vec<String>,
+{
+    mutate_with_notes(source_code, structs, mutation_count, |source_code, node| {
+        replacements(source_code, node)
+            .into_iter()
+            .map(|n| (n, String::new()))
+            .collect()
+    })
+}
+
+// mutate_with와 같지만 대체 코드마다 provenance에 남길 설명(예: "+ -> <<")을 같이 받는다.
+pub fn mutate_with_notes<F>(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+    replacements: F,
+) -> Vec<String>
+where
+    F: Fn(&str, Node) -> Vec<(String, String)>,
+{
+    mutate_with_gates(source_code, structs, mutation_count, |source_code, node| {
+        replacements(source_code, node)
+            .into_iter()
+            .map(|(n, note)| (n, note, ""))
+            .collect()
+    })
+}
+
+// 대체 코드, provenance 설명, 같이 켜야 하는 feature gate
+pub type Replacement = (String, String, &'static str);
+
+// mutate_with_notes와 같지만 대체 코드마다 같이 켜야 하는 feature gate를 받는다.
+// gate가 비어있지 않고 파일에 아직 없으면 mutant 맨 앞에 #![feature(gate)] 를 넣는다.
+pub fn mutate_with_gates<F>(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+    replacements: F,
+) -> Vec<String>
+where
+    F: Fn(&str, Node) -> Vec<Replacement>,
+{
+    let tree = parse(source_code);
+    let existing_gates = attribute::harvest(source_code);
+    let mut modified_versions = Vec::new();
+    let mut index = 0;
+
+    // 대체 코드는 고른 node에 대해서만 만든다. mode 12처럼 node 하나가 큰 대체 코드를 여러 개 만드는 경우가 있다
+    let candidates = |info: &TypePosInfo| -> Vec<Replacement> {
+        let Some(node) = find_node(&tree, info) else {
+            return vec![];
+        };
+        let original = node_text(source_code, node);
+        replacements(source_code, node)
+            .into_iter()
+            .filter(|(n, _, _)| n != original)
+            .collect()
+    };
+
+    let mut emit = |info: &TypePosInfo, (n, note, gate): &Replacement| {
+        let &(type_string, start_byte, end_byte, start_point, end_point) = info;
+        let before = &source_code[..start_byte];
+        let after = &source_code[end_byte..];
+        let original = &source_code[start_byte..end_byte];
+        let gate = if gate.is_empty() || existing_gates.iter().any(|g| g == gate) {
+            String::new()
+        } else {
+            format!("#![feature({})]\n", gate)
+        };
+        modified_versions.push(format!("{}{}{}{}", gate, before, n, after));
+        index += 1;
+        if note.is_empty() {
+            println!(
+                "[{}] {}-{} {} : {} -> {}",
+                index, start_point, end_point, type_string, original, n
+            );
+        } else {
+            println!(
+                "[{}] {}-{} {} ({}) : {} -> {}",
+                index, start_point, end_point, type_string, note, original, n
+            );
+        }
+    };
+
+    if mutation_count == 0 {
+        for info in structs.iter() {
+            for n in candidates(info).iter() {
+                emit(info, n);
+            }
+        }
+    } else {
+        // 대체 코드가 없는 node를 뽑으면 빼고 다시 뽑는다. 한 번 만든 대체 코드는 다시 쓴다
+        let mut rng = rand::thread_rng();
+        let mut cache: HashMap<usize, Vec<Replacement>> = HashMap::new();
+        let mut open: Vec<usize> = (0..structs.len()).collect();
+        for _ in 0..mutation_count {
+            while !open.is_empty() {
+                let pick = rng.gen_range(0..open.len());
+                let i = open[pick];
+                let exprs = cache.entry(i).or_insert_with(|| candidates(&structs[i]));
+                if let Some(n) = exprs.choose(&mut rng) {
+                    emit(&structs[i], n);
+                    break;
+                }
+                open.swap_remove(pick);
+            }
+        }
+    }
+
+    modified_versions
+}
+
+// node 안의 자식 하나만 new_text로 바꾼 node 전체의 코드.
+pub fn replace_child(source_code: &str, node: Node, child: Node, new_text: &str) -> String {
+    format!(
+        "{}{}{}",
+        &source_code[node.start_byte()..child.start_byte()],
+        new_text,
+        &source_code[child.end_byte()..node.end_byte()]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::get_splice_parts;
+
+    #[test]
+    fn replacements_are_built_only_for_the_picked_node() {
+        let source_code = "fn f() { let a = 1; let b = 2; }";
+        let nodes = get_splice_parts(&source_code.to_string());
+        let calls = Cell::new(0);
+        let mutants = mutate_with(source_code, &nodes, 1, |_, _| {
+            calls.set(calls.get() + 1);
+            vec!["x".to_string()]
+        });
+        assert_eq!(mutants.len(), 1);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn nodes_without_replacements_are_skipped() {
+        let source_code = "fn f() { let a = 1; }";
+        let nodes = get_splice_parts(&source_code.to_string());
+        let mutants = mutate_with(source_code, &nodes, 5, |source_code, node| {
+            if node.kind() == "integer_literal" {
+                vec!["2".to_string(), node_text(source_code, node).to_string()]
+            } else {
+                vec![]
+            }
+        });
+        assert_eq!(mutants, vec!["fn f() { let a = 2; }"; 5]);
+        assert!(mutate_with(source_code, &nodes, 3, |_, _| vec![]).is_empty());
+    }
+
+    #[test]
+    fn gates_are_added_once() {
+        let source_code = "#![feature(box_patterns)]\nfn f() {}";
+        let nodes = get_splice_parts(&source_code.to_string());
+        let mutants = mutate_with_gates(source_code, &nodes, 0, |_, node| {
+            if node.kind() == "block" {
+                vec![
+                    ("{ 1 }".to_string(), String::new(), "box_patterns"),
+                    ("{ 2 }".to_string(), String::new(), "never_type"),
+                ]
+            } else {
+                vec![]
+            }
+        });
+        assert_eq!(
+            mutants,
+            [
+                "#![feature(box_patterns)]\nfn f() { 1 }",
+                "#![feature(never_type)]\n#![feature(box_patterns)]\nfn f() { 2 }"
+            ]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_notes, node_text, replace_child};
+use crate::TypePosInfo;
+
+// 연산자를 바꾸는 mutator.
+// 같은 우선순위 class 안에서 바꾸기, 다른 class로 바꾸기, .. <-> ..=,
+// 단항 연산자(- ! * & &mut) 붙이고 떼기, operand에 cast 씌우기를 한다.
+
+const PRECEDENCE_CLASSES: [&[&str]; 8] = [
+    &["*", "/", "%"],
+    &["+", "-"],
+    &["<<", ">>"],
+    &["&", "^", "|"],
+    &["==", "!="],
+    &["<", ">", "<=", ">="],
+    &["&&", "||"],
+    // 다른 class로 넘어갈 때 쓰는 대표 연산자들
+    &["*", "+", "<<", "&", "==", "<", "&&"],
+];
+
+const COMPOUND_OPS: [&str; 10] = ["+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>="];
+
+const UNARY_PREFIXES: [&str; 5] = ["-", "!", "*", "&", "&mut "];
+
+const CASTS: [&str; 6] = [
+    "as u8",
+    "as *const _",
+    "as i128",
+    "as f64",
+    "as usize",
+    "as *mut u64",
+];
+
+// 이런 node는 앞에 단항 연산자를 붙이거나 cast를 해도 괄호가 필요 없다
+fn is_atom(node: Node) -> bool {
+    node.kind() == "identifier"
+        || node.kind().ends_with("_literal")
+        || matches!(
+            node.kind(),
+            "call_expression"
+                | "field_expression"
+                | "index_expression"
+                | "parenthesized_expression"
+                | "scoped_identifier"
+                | "self"
+        )
+}
+
+fn paren(source_code: &str, node: Node) -> String {
+    let text = node_text(source_code, node);
+    if is_atom(node) {
+        text.to_string()
+    } else {
+        format!("({})", text)
+    }
+}
+
+// binary/compound/range의 operand에 단항 연산자를 붙이거나 cast를 씌운다.
+fn operand_mutations(source_code: &str, node: Node, operand: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    for prefix in UNARY_PREFIXES {
+        let new_operand = format!("{}{}", prefix, paren(source_code, operand));
+        exprs.push((
+            replace_child(source_code, node, operand, &new_operand),
+            format!("add {}", prefix.trim()),
+        ));
+    }
+    for cast in CASTS {
+        let new_operand = format!("({} {})", paren(source_code, operand), cast);
+        exprs.push((
+            replace_child(source_code, node, operand, &new_operand),
+            cast.to_string(),
+        ));
+    }
+    exprs
+}
+
+fn binary_replacements(source_code: &str, node: Node, ops: &[&str]) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    if let Some(operator) = node.child_by_field_name("operator") {
+        let op = operator.kind();
+        // 같은 class의 연산자 먼저, 그다음 다른 class 대표 연산자
+        let mut new_ops: Vec<&str> = vec![];
+        let same_class = PRECEDENCE_CLASSES[..7].iter().filter(|c| c.contains(&op));
+        for &new_op in same_class.flat_map(|c| c.iter()).chain(ops.iter()) {
+            if !new_ops.contains(&new_op) {
+                new_ops.push(new_op);
+            }
+        }
+        for new_op in new_ops {
+            if new_op != op {
+                exprs.push((
+                    replace_child(source_code, node, operator, new_op),
+                    format!("{} -> {}", op, new_op),
+                ));
+            }
+        }
+    }
+    for field in ["left", "right"] {
+        if let Some(operand) = node.child_by_field_name(field) {
+            exprs.append(&mut operand_mutations(source_code, node, operand));
+        }
+    }
+    exprs
+}
+
+fn unary_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let (Some(operator), Some(operand)) = (node.child(0), node.child(1)) else {
+        return exprs;
+    };
+    let op = operator.kind();
+    // 연산자 떼기
+    exprs.push((
+        node_text(source_code, operand).to_string(),
+        format!("remove {}", op),
+    ));
+    // 다른 단항 연산자로 바꾸기, 두 번 붙이기
+    for new_op in ["-", "!", "*"] {
+        if new_op != op {
+            exprs.push((
+                replace_child(source_code, node, operator, new_op),
+                format!("{} -> {}", op, new_op),
+            ));
+        }
+    }
+    exprs.push((
+        format!("{}{}", op, node_text(source_code, node)),
+        format!("add {}", op),
+    ));
+    exprs
+}
+
+fn reference_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let Some(value) = node.child_by_field_name("value") else {
+        return exprs;
+    };
+    let value_text = node_text(source_code, value);
+    let is_mut = node_text(source_code, node).starts_with("&mut");
+    exprs.push((value_text.to_string(), "remove &".to_string()));
+    if is_mut {
+        exprs.push((format!("&{}", value_text), "&mut -> &".to_string()));
+    } else {
+        exprs.push((format!("&mut {}", value_text), "& -> &mut".to_string()));
+    }
+    exprs.push((format!("&&{}", value_text), "add &".to_string()));
+    exprs.push((
+        format!("(&{} as *const _)", value_text),
+        "as *const _".to_string(),
+    ));
+    exprs
+}
+
+fn range_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    let Some(operator) = children
+        .iter()
+        .find(|c| matches!(c.kind(), ".." | "..=" | "..."))
+    else {
+        return exprs;
+    };
+    let op = operator.kind();
+    for new_op in ["..", "..=", "..."] {
+        if new_op != op {
+            exprs.push((
+                replace_child(source_code, node, *operator, new_op),
+                format!("{} -> {}", op, new_op),
+            ));
+        }
+    }
+    let start = children
+        .iter()
+        .find(|c| c.end_byte() <= operator.start_byte());
+    let end = children
+        .iter()
+        .find(|c| c.start_byte() >= operator.end_byte());
+    let start_text = start.map(|n| node_text(source_code, *n)).unwrap_or("");
+    let end_text = end.map(|n| node_text(source_code, *n)).unwrap_or("");
+    // 시작이나 끝을 빼거나, 뒤집기
+    exprs.push((format!("{}{}", start_text, op), "remove end".to_string()));
+    exprs.push((format!("{}{}", op, end_text), "remove start".to_string()));
+    exprs.push((
+        format!("{}{}{}", end_text, op, start_text),
+        "swap".to_string(),
+    ));
+    for operand in [start, end].into_iter().flatten() {
+        exprs.append(&mut operand_mutations(source_code, node, *operand));
+    }
+    exprs
+}
+
+pub fn operator_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    match node.kind() {
+        "binary_expression" => binary_replacements(source_code, node, PRECEDENCE_CLASSES[7]),
+        "compound_assignment_expr" => binary_replacements(source_code, node, &COMPOUND_OPS),
+        "unary_expression" => unary_replacements(source_code, node),
+        "reference_expression" => reference_replacements(source_code, node),
+        "range_expression" => range_replacements(source_code, node),
+        _ => vec![],
+    }
+}
+
+pub fn mutate_operator(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_notes(source_code, structs, mutation_count, operator_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_operator(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, bodies: &[&str]) {
+        let all = mutants(source_code);
+        for body in bodies {
+            let mutant = format!("fn f(a: u8, b: u8) {{ {} }}", body);
+            assert!(all.contains(&mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn binary_operators_swap_within_and_across_classes() {
+        assert_mutants(
+            "fn f(a: u8, b: u8) { a + b; }",
+            &[
+                "a - b;",
+                "a * b;",
+                "a << b;",
+                "a && b;",
+                "-a + b;",
+                "a + (b as u8);",
+            ],
+        );
+        assert!(!mutants("fn f(a: u8, b: u8) { a + b; }")
+            .contains(&"fn f(a: u8, b: u8) { a + b; }".to_string()));
+    }
+
+    #[test]
+    fn non_atom_operands_get_parentheses() {
+        assert_mutants(
+            "fn f(a: u8, b: u8) { a + b * 2; }",
+            &["a + !(b * 2);", "a + ((b * 2) as i128);"],
+        );
+    }
+
+    #[test]
+    fn compound_assignment_and_unary() {
+        assert_mutants("fn f(a: u8, b: u8) { a += b; }", &["a <<= b;", "a %= b;"]);
+        assert_mutants("fn f(a: u8, b: u8) { !a; }", &["a;", "-a;", "*a;", "!!a;"]);
+    }
+
+    #[test]
+    fn references_and_ranges() {
+        assert_mutants(
+            "fn f(a: u8, b: u8) { &a; }",
+            &["a;", "&mut a;", "&&a;", "(&a as *const _);"],
+        );
+        assert_mutants(
+            "fn f(a: u8, b: u8) { a..b; }",
+            &["a..=b;", "a...b;", "a..;", "..b;", "b..a;"],
+        );
+    }
+}
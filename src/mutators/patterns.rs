@@ -0,0 +1,355 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_gates, node_text, Replacement};
+use crate::TypePosInfo;
+
+// pattern과 match arm을 바꾸는 mutator.
+// exhaustiveness check와 pattern lowering에서 ICE가 자주 나는데, 지금은 pattern을 지우거나
+// 같은 kind끼리 바꾸는 것밖에 없다. 여기서는 ref/mut/@ binding, _ 와 .., or-pattern, guard,
+// 빠진 arm과 겹치는 arm을 만든다.
+
+const GUARDS: [&str; 6] = [
+    "if true",
+    "if false",
+    "if let Some(_) = None::<()>",
+    "if let _ = ()",
+    "if let true = true && let false = false",
+    "if { loop {} }",
+];
+
+// 끝에 붙여서 뒤 arm을 전부 unreachable로 만들거나, 빠진 경우를 채우는 arm
+const CATCH_ALL_ARMS: [&str; 3] = [
+    "_ => loop {},",
+    "_ if false => panic!(),",
+    "ref _x => todo!(),",
+];
+
+// pattern 자리에 있는 identifier인지. Some(x) 의 Some이나 guard 안의 x는 빼야 한다.
+fn is_binding(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    if ["type", "condition", "value", "name"]
+        .iter()
+        .any(|f| parent.child_by_field_name(f) == Some(node))
+    {
+        return false;
+    }
+    parent.kind().ends_with("_pattern")
+        || (matches!(
+            parent.kind(),
+            "let_declaration" | "parameter" | "for_expression" | "let_condition"
+        ) && parent.child_by_field_name("pattern") == Some(node))
+}
+
+fn is_pattern(node: Node) -> bool {
+    match node.kind() {
+        "match_pattern" | "remaining_field_pattern" | "field_pattern" => false,
+        "identifier" => is_binding(node),
+        kind => kind.ends_with("_pattern"),
+    }
+}
+
+fn named_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|c| !c.kind().ends_with("comment"))
+        .collect()
+}
+
+// 모든 pattern에 공통으로 할 수 있는 것들. box pattern만 feature gate가 필요하다
+fn pattern_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let text = node_text(source_code, node);
+    let mut exprs = vec![
+        ("_".to_string(), "-> _".to_string(), ""),
+        (
+            format!("{} | {}", text, text),
+            "or with itself".to_string(),
+            "",
+        ),
+        (format!("({} | _)", text), "or with _".to_string(), ""),
+        (
+            format!("({} | ({} | _))", text, text),
+            "nested or".to_string(),
+            "",
+        ),
+        (format!("_x @ {}", text), "add @".to_string(), ""),
+        (format!("ref _x @ {}", text), "add ref @".to_string(), ""),
+        (format!("&{}", text), "add &".to_string(), ""),
+        (format!("&mut {}", text), "add &mut".to_string(), ""),
+        (
+            format!("box {}", text),
+            "add box".to_string(),
+            "box_patterns",
+        ),
+    ];
+    if node.kind() == "identifier" {
+        for prefix in ["ref ", "ref mut ", "mut "] {
+            exprs.push((
+                format!("{}{}", prefix, text),
+                format!("add {}", prefix.trim()),
+                "",
+            ));
+        }
+    }
+    exprs
+}
+
+// 감싸고 있는 것을 떼어내기: ref x -> x, x @ p -> p, &p -> p, a | b -> a
+fn unwrap_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let children = named_children(node);
+    let mut exprs = vec![];
+    match node.kind() {
+        "ref_pattern" | "mut_pattern" | "reference_pattern" | "captured_pattern" => {
+            if let Some(inner) = children.iter().rev().find(|c| is_pattern(**c)) {
+                exprs.push((
+                    node_text(source_code, *inner).to_string(),
+                    format!("remove {}", node.kind().trim_end_matches("_pattern")),
+                ));
+            }
+        }
+        "or_pattern" => {
+            for alternative in children.iter() {
+                exprs.push((
+                    node_text(source_code, *alternative).to_string(),
+                    "remove alternative".to_string(),
+                ));
+            }
+        }
+        _ => {}
+    }
+    exprs
+}
+
+// (a, b) [a, b] S(a, b) 의 원소를 .. 로 바꾸거나 .. 를 더하기. S { a, .. } 의 .. 를 더하고 빼기
+fn rest_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let text = node_text(source_code, node);
+    let elements: Vec<Node> = named_children(node)
+        .into_iter()
+        .filter(|c| node.child_by_field_name("type") != Some(*c))
+        .collect();
+    match node.kind() {
+        "tuple_pattern" | "slice_pattern" | "tuple_struct_pattern" => {
+            let (Some(first), Some(last)) = (elements.first(), elements.last()) else {
+                return exprs;
+            };
+            let open = &source_code[node.start_byte()..first.start_byte()];
+            let close = &source_code[last.end_byte()..node.end_byte()];
+            let items: Vec<&str> = elements
+                .iter()
+                .map(|e| node_text(source_code, *e))
+                .collect();
+            for i in 0..items.len() {
+                let mut rest = items.clone();
+                rest[i] = "..";
+                exprs.push((
+                    format!("{}{}{}", open, rest.join(", "), close),
+                    format!("{} -> ..", items[i]),
+                ));
+            }
+            exprs.push((
+                format!("{}{}, ..{}", open, items.join(", "), close),
+                "add ..".to_string(),
+            ));
+            exprs.push((
+                format!("{}.., {}, ..{}", open, items.join(", "), close),
+                "add .. twice".to_string(),
+            ));
+        }
+        "struct_pattern" => {
+            let fields: Vec<&str> = elements
+                .iter()
+                .filter(|e| e.kind() == "field_pattern")
+                .map(|e| node_text(source_code, *e))
+                .collect();
+            let has_rest = elements
+                .iter()
+                .any(|e| e.kind() == "remaining_field_pattern");
+            if let Some(ty) = node.child_by_field_name("type") {
+                let ty = node_text(source_code, ty);
+                if has_rest {
+                    exprs.push((
+                        format!("{} {{ {} }}", ty, fields.join(", ")),
+                        "remove ..".to_string(),
+                    ));
+                } else {
+                    exprs.push((
+                        format!(
+                            "{} {{ {}{}.. }}",
+                            ty,
+                            fields.join(", "),
+                            if fields.is_empty() { "" } else { ", " }
+                        ),
+                        "add ..".to_string(),
+                    ));
+                }
+                exprs.push((format!("{} {{ .. }}", ty), "only ..".to_string()));
+                for i in 0..fields.len() {
+                    let mut rest = fields.clone();
+                    rest.remove(i);
+                    exprs.push((
+                        format!("{} {{ {} }}", ty, rest.join(", ")),
+                        format!("remove field {}", fields[i]),
+                    ));
+                }
+            }
+        }
+        "range_pattern" => {
+            if let (Some(start), Some(end)) = (elements.first(), elements.get(1)) {
+                let (a, b) = (node_text(source_code, *start), node_text(source_code, *end));
+                for (range, note) in [
+                    (format!("{}..{}", a, b), "exclusive"),
+                    (format!("{}..={}", a, b), "inclusive"),
+                    (format!("{}..", a), "remove end"),
+                    (format!("..={}", b), "remove start"),
+                    (format!("{}..={}", b, a), "swap"),
+                    (format!("{}..={}", a, a), "empty"),
+                ] {
+                    if range != text {
+                        exprs.push((range, note.to_string()));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+    exprs
+}
+
+fn match_pattern_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    match node.child_by_field_name("condition") {
+        Some(condition) => {
+            let pattern = &source_code[node.start_byte()..condition.start_byte()];
+            let pattern = pattern.trim_end();
+            let pattern = pattern.strip_suffix("if").unwrap_or(pattern).trim_end();
+            exprs.push((pattern.to_string(), "remove guard".to_string()));
+            exprs.push((
+                format!(
+                    "{} if let true = ({})",
+                    pattern,
+                    node_text(source_code, condition)
+                ),
+                "if -> if let".to_string(),
+            ));
+        }
+        None => {
+            let text = node_text(source_code, node);
+            for guard in GUARDS {
+                exprs.push((format!("{} {}", text, guard), format!("add {}", guard)));
+            }
+        }
+    }
+    exprs
+}
+
+fn match_block_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let mut exprs = vec![];
+    let arms: Vec<String> = named_children(node)
+        .into_iter()
+        .filter(|c| c.kind() == "match_arm")
+        .map(|arm| {
+            let text = node_text(source_code, arm);
+            if text.ends_with(',') || text.ends_with('}') {
+                text.to_string()
+            } else {
+                format!("{},", text)
+            }
+        })
+        .collect();
+    let render = |arms: &[String]| format!("{{ {} }}", arms.join(" "));
+    // arm 하나를 빼서 non-exhaustive로
+    for i in 0..arms.len() {
+        let mut rest = arms.clone();
+        rest.remove(i);
+        exprs.push((render(&rest), format!("remove arm {}", i + 1)));
+    }
+    // arm을 복제하거나 맨 뒤 arm을 맨 앞으로 올려서 겹치게
+    for i in 0..arms.len() {
+        let mut overlapping = arms.clone();
+        overlapping.insert(i, arms[i].clone());
+        exprs.push((render(&overlapping), format!("duplicate arm {}", i + 1)));
+    }
+    if arms.len() > 1 {
+        let mut reordered = arms.clone();
+        let last = reordered.pop().unwrap();
+        reordered.insert(0, last);
+        exprs.push((render(&reordered), "last arm first".to_string()));
+    }
+    for arm in CATCH_ALL_ARMS {
+        let mut first = arms.clone();
+        first.insert(0, arm.to_string());
+        exprs.push((render(&first), format!("add {} first", arm)));
+        let mut last = arms.clone();
+        last.push(arm.to_string());
+        exprs.push((render(&last), format!("add {} last", arm)));
+    }
+    exprs.push((render(&[]), "remove all arms".to_string()));
+    exprs
+}
+
+fn patterns_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let exprs = match node.kind() {
+        "match_block" => match_block_replacements(source_code, node),
+        "match_pattern" => match_pattern_replacements(source_code, node),
+        _ if is_pattern(node) => {
+            let mut exprs = unwrap_replacements(source_code, node);
+            exprs.append(&mut rest_replacements(source_code, node));
+            let mut gated = pattern_replacements(source_code, node);
+            gated.extend(exprs.into_iter().map(|(n, note)| (n, note, "")));
+            return gated;
+        }
+        _ => vec![],
+    };
+    exprs.into_iter().map(|(n, note)| (n, note, "")).collect()
+}
+
+pub fn mutate_patterns(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_gates(source_code, structs, mutation_count, patterns_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_patterns(source_code, &nodes, 0)
+    }
+
+    #[test]
+    fn box_pattern_gets_feature_gate() {
+        let source_code = "fn f(b: Box<u8>) { let x = b; }";
+        let boxed: Vec<String> = mutants(source_code)
+            .into_iter()
+            .filter(|m| m.contains("box x"))
+            .collect();
+        assert_eq!(
+            boxed,
+            ["#![feature(box_patterns)]\nfn f(b: Box<u8>) { let box x = b; }"]
+        );
+    }
+
+    #[test]
+    fn only_box_pattern_is_gated() {
+        assert!(mutants("fn f() { let (a, b) = (1, 2); }")
+            .iter()
+            .all(|m| m.starts_with("#![feature(box_patterns)]") == m.contains("box ")));
+    }
+
+    #[test]
+    fn tuple_elements_become_rest() {
+        let source_code = "fn f() { let (a, b) = (1, 2); }";
+        let all = mutants(source_code);
+        for pattern in ["(.., b)", "(a, ..)", "(a, b, ..)", "(.., a, b, ..)"] {
+            let mutant = format!("fn f() {{ let {} = (1, 2); }}", pattern);
+            assert!(all.contains(&mutant), "{}", mutant);
+        }
+    }
+}
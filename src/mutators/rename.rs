@@ -0,0 +1,465 @@
+use rand::seq::SliceRandom;
+use tree_sitter::Node;
+
+use super::{find_node, node_text, parse};
+use crate::TypePosInfo;
+
+// 이름을 일관되게 바꾸는 mutator.
+// mutate_self는 identifier 하나만 바꿔서 대부분 "cannot find value" 로 끝나버린다.
+// 여기서는 선언(let, parameter, generic parameter, lifetime, item 이름)을 찾고
+// 그 선언을 가리키는 같은 이름(local이면 선언 뒤부터 다음 shadowing 전까지)을 같이 바꿔서 name resolution을 통과하게 한다.
+// 다른 이름과 일부러 겹치게(shadowing, type/value/macro namespace 충돌) 바꾸기도 한다.
+
+// 새 이름 후보에 항상 넣는 것들. primitive, prelude, 자주 쓰는 macro 이름과 겹치게 만든다.
+const COLLIDING_VALUE_NAMES: [&str; 8] = [
+    "main", "drop", "Some", "None", "println", "vec", "i32", "str",
+];
+const COLLIDING_TYPE_NAMES: [&str; 8] = [
+    "Self", "Box", "Option", "String", "i32", "str", "Sized", "Copy",
+];
+
+const FILE_NAMES_PER_DECL: usize = 8;
+
+struct Rename<'t> {
+    decl: Node<'t>,
+    scope: Node<'t>,
+    // scope 안에서 바꿀 node 종류
+    kinds: &'static [&'static str],
+    // 이 byte 뒤의 이름만 이 선언을 가리킨다. item과 generic parameter는 scope 처음부터
+    from: usize,
+    old: String,
+}
+
+// classify가 돌려주는 (scope, 바꿀 node 종류, 쓰임이 시작되는 byte)
+type Scope<'t> = (Node<'t>, &'static [&'static str], usize);
+
+fn ancestor<'t>(node: Node<'t>, kinds: &[&str]) -> Option<Node<'t>> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if kinds.contains(&n.kind()) {
+            return Some(n);
+        }
+        current = n.parent();
+    }
+    None
+}
+
+fn is_field_of(node: Node, parent: Node, field: &str) -> bool {
+    parent
+        .child_by_field_name(field)
+        .map(|f| f.start_byte() <= node.start_byte() && node.end_byte() <= f.end_byte())
+        .unwrap_or(false)
+}
+
+fn root(node: Node) -> Node {
+    let mut node = node;
+    while let Some(parent) = node.parent() {
+        node = parent;
+    }
+    node
+}
+
+// 이 node가 어떤 이름을 선언하는 자리라면, 그 이름이 유효한 scope를 돌려준다.
+// let, parameter 같은 local binding은 선언 뒤부터 쓰이고, let x = x; 의 오른쪽 x는 앞의 x다.
+fn classify(node: Node) -> Option<Scope> {
+    let parent = node.parent()?;
+    match node.kind() {
+        "identifier" => {
+            if matches!(parent.kind(), "lifetime" | "label") {
+                return None;
+            }
+            if let Some(binding) = ancestor(
+                node,
+                &[
+                    "let_declaration",
+                    "parameter",
+                    "for_expression",
+                    "match_arm",
+                    "closure_parameters",
+                ],
+            ) {
+                let scope = match binding.kind() {
+                    // let 패턴 안의 binding은 let이 들어있는 block의 나머지가 scope
+                    "let_declaration" if is_field_of(node, binding, "pattern") => {
+                        binding.parent().map(|s| (s, binding.end_byte()))
+                    }
+                    "parameter" if is_field_of(node, binding, "pattern") => {
+                        ancestor(binding, &["function_item", "closure_expression"])
+                            .map(|s| (s, node.end_byte()))
+                    }
+                    // for x in x.iter() 의 오른쪽 x는 바깥 x다
+                    "for_expression" if is_field_of(node, binding, "pattern") => binding
+                        .child_by_field_name("body")
+                        .map(|body| (binding, body.start_byte())),
+                    "match_arm" if is_field_of(node, binding, "pattern") => {
+                        Some((binding, node.end_byte()))
+                    }
+                    "closure_parameters" => binding.parent().map(|s| (s, node.end_byte())),
+                    _ => None,
+                };
+                if let Some((scope, from)) = scope {
+                    return Some((scope, &["identifier"], from));
+                }
+            }
+            if matches!(
+                parent.kind(),
+                "function_item" | "const_item" | "static_item" | "macro_definition" | "mod_item"
+            ) && is_field_of(node, parent, "name")
+            {
+                let scope = root(node);
+                return Some((scope, &["identifier"], scope.start_byte()));
+            }
+            None
+        }
+        "type_identifier" => {
+            if matches!(
+                parent.kind(),
+                "struct_item" | "enum_item" | "trait_item" | "type_item" | "union_item"
+            ) && is_field_of(node, parent, "name")
+            {
+                let scope = root(node);
+                return Some((
+                    scope,
+                    &["type_identifier", "identifier"],
+                    scope.start_byte(),
+                ));
+            }
+            // generic parameter는 그걸 선언한 item 전체가 scope
+            let params = match parent.kind() {
+                "type_parameters" => Some(parent),
+                "constrained_type_parameter" if is_field_of(node, parent, "left") => {
+                    parent.parent()
+                }
+                "optional_type_parameter" if is_field_of(node, parent, "name") => parent.parent(),
+                _ => None,
+            }?;
+            let scope = params.parent()?;
+            Some((
+                scope,
+                &["type_identifier", "identifier"],
+                scope.start_byte(),
+            ))
+        }
+        "lifetime" => {
+            let params = match parent.kind() {
+                "type_parameters" => Some(parent),
+                "constrained_type_parameter" if is_field_of(node, parent, "left") => {
+                    parent.parent()
+                }
+                _ => None,
+            }?;
+            let scope = params.parent()?;
+            Some((scope, &["lifetime"], scope.start_byte()))
+        }
+        _ => None,
+    }
+}
+
+// type 이름을 바꿀 때 같이 바꿀 identifier: S::new 의 S, tuple struct 생성자 S(..) 와 pattern S(..).
+// 같은 글자인 변수나 함수는 value namespace라 건드리지 않는다
+fn is_type_use(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        "scoped_identifier" => is_field_of(node, parent, "path"),
+        "call_expression" => is_field_of(node, parent, "function"),
+        "tuple_struct_pattern" => is_field_of(node, parent, "type"),
+        _ => false,
+    }
+}
+
+// rename이 local binding이면, scope 안에서 같은 이름을 다시 선언하는 binding들이 가리는 범위.
+// let x 가 다시 나오면 그 let 뒤부터 block 끝까지, 안쪽 closure나 match arm의 x는 그 안 전체.
+fn shadowed_ranges(source_code: &str, rename: &Rename) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    if rename.kinds != ["identifier"] || rename.from == rename.scope.start_byte() {
+        return ranges;
+    }
+    let mut stack = vec![rename.scope];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "identifier"
+            && node != rename.decl
+            && node_text(source_code, node) == rename.old
+        {
+            if let Some((scope, ["identifier"], from)) = classify(node) {
+                if scope != root(node) {
+                    ranges.push((node.start_byte(), node.end_byte()));
+                    ranges.push((from, scope.end_byte()));
+                }
+            }
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    ranges
+}
+
+// scope 안에서 kinds에 속하고 글자가 old와 같은, 이 선언을 가리키는 node를 전부 new로 바꾼 scope 코드
+fn rename_in_scope(source_code: &str, rename: &Rename, new: &str) -> String {
+    let shadowed = shadowed_ranges(source_code, rename);
+    let is_use = |node: Node| {
+        if node == rename.decl {
+            return true;
+        }
+        if node.start_byte() < rename.from
+            || shadowed
+                .iter()
+                .any(|&(start, end)| start <= node.start_byte() && node.end_byte() <= end)
+        {
+            return false;
+        }
+        node.kind() != "identifier" || rename.kinds == ["identifier"] || is_type_use(node)
+    };
+    let mut occurrences = vec![];
+    let mut stack = vec![rename.scope];
+    while let Some(node) = stack.pop() {
+        if rename.kinds.contains(&node.kind())
+            && node_text(source_code, node) == rename.old
+            && is_use(node)
+        {
+            occurrences.push(node);
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    occurrences.sort_by_key(|n| n.start_byte());
+
+    let mut out = String::new();
+    let mut last = rename.scope.start_byte();
+    for n in occurrences {
+        // lifetime 안의 identifier처럼 이미 바꾼 범위 안쪽은 건너뛴다
+        if n.start_byte() < last {
+            continue;
+        }
+        out.push_str(&source_code[last..n.start_byte()]);
+        out.push_str(new);
+        last = n.end_byte();
+    }
+    out.push_str(&source_code[last..rename.scope.end_byte()]);
+    out
+}
+
+fn new_names(source_code: &str, structs: &[TypePosInfo], rename: &Rename) -> Vec<String> {
+    let text = |kind: &str| -> Vec<String> {
+        structs
+            .iter()
+            .filter(|(k, ..)| *k == kind)
+            .map(|&(_, s, e, _, _)| source_code[s..e].to_string())
+            .collect()
+    };
+    // 파일 안의 이름은 너무 많을 수 있어서 선언 하나당 FILE_NAMES_PER_DECL 개까지만 쓴다
+    let mut from_file: Vec<String> = vec![];
+    let mut names: Vec<String> = vec![];
+    if rename.kinds.contains(&"lifetime") {
+        names.push("'renamed".to_string());
+        from_file.extend(text("lifetime"));
+    } else if rename.kinds.contains(&"type_identifier") {
+        // type 이름을 다른 type, value, 내장 type 이름과 충돌시킨다
+        names.push(format!("{}Renamed", rename.old));
+        names.extend(COLLIDING_TYPE_NAMES.iter().map(|s| s.to_string()));
+        from_file.extend(text("type_identifier"));
+        from_file.extend(text("identifier"));
+    } else {
+        // value 이름을 다른 value(= shadowing), type, macro 이름과 충돌시킨다
+        names.push(format!("{}_renamed", rename.old));
+        names.extend(COLLIDING_VALUE_NAMES.iter().map(|s| s.to_string()));
+        from_file.extend(text("identifier"));
+        from_file.extend(text("type_identifier"));
+    }
+    from_file.retain(|n| n != &rename.old);
+    from_file.sort();
+    from_file.dedup();
+    from_file.shuffle(&mut rand::thread_rng());
+    from_file.truncate(FILE_NAMES_PER_DECL);
+    names.extend(from_file);
+
+    let mut unique: Vec<String> = vec![];
+    for n in names {
+        if n != rename.old && !n.is_empty() && !unique.contains(&n) {
+            unique.push(n);
+        }
+    }
+    unique
+}
+
+// let x = ..; 바로 뒤에 같은 이름으로 다시 binding 하는 문장을 끼워 넣는다.
+fn shadowings(rename: &Rename) -> Vec<(usize, String)> {
+    let mut out = vec![];
+    if let Some(stmt) = ancestor(rename.decl, &["let_declaration"]) {
+        if stmt.child_by_field_name("pattern").map(|p| p.kind()) == Some("identifier") {
+            let x = &rename.old;
+            for shadow in [
+                format!(" let {} = {};", x, x),
+                format!(" let {} = &{};", x, x),
+                format!(" let mut {} = {};", x, x),
+                format!(" let {} = || {};", x, x),
+            ] {
+                out.push((stmt.end_byte(), shadow));
+            }
+        }
+    }
+    out
+}
+
+pub fn mutate_rename(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    let tree = parse(source_code);
+    let mut renames: Vec<Rename> = vec![];
+    for info in structs.iter() {
+        if let Some(node) = find_node(&tree, info) {
+            if let Some((scope, kinds, from)) = classify(node) {
+                renames.push(Rename {
+                    decl: node,
+                    scope,
+                    kinds,
+                    from,
+                    old: node_text(source_code, node).to_string(),
+                });
+            }
+        }
+    }
+
+    // (어떤 선언을, 무엇으로) 를 먼저 다 모아두고, 실제 코드는 고른 것만 만든다
+    enum Edit {
+        Rename(String),
+        Shadow(usize, String),
+    }
+    let mut candidates: Vec<(&Rename, Edit)> = vec![];
+    for rename in renames.iter() {
+        for name in new_names(source_code, structs, rename) {
+            candidates.push((rename, Edit::Rename(name)));
+        }
+        for (at, stmt) in shadowings(rename) {
+            candidates.push((rename, Edit::Shadow(at, stmt)));
+        }
+    }
+
+    let chosen: Vec<&(&Rename, Edit)> = if mutation_count == 0 {
+        candidates.iter().collect()
+    } else {
+        (0..mutation_count)
+            .filter_map(|_| candidates.choose(&mut rand::thread_rng()))
+            .collect()
+    };
+
+    let mut modified_versions = Vec::new();
+    for (index, (rename, edit)) in chosen.into_iter().enumerate() {
+        let (start_point, end_point) = (rename.decl.start_position(), rename.decl.end_position());
+        match edit {
+            Edit::Rename(new) => {
+                let before = &source_code[..rename.scope.start_byte()];
+                let after = &source_code[rename.scope.end_byte()..];
+                let scope = rename_in_scope(source_code, rename, new);
+                modified_versions.push(format!("{}{}{}", before, scope, after));
+                println!(
+                    "[{}] {}-{} {} (rename in {}) : {} -> {}",
+                    index + 1,
+                    start_point,
+                    end_point,
+                    rename.decl.kind(),
+                    rename.scope.kind(),
+                    rename.old,
+                    new
+                );
+            }
+            Edit::Shadow(at, stmt) => {
+                modified_versions.push(format!(
+                    "{}{}{}",
+                    &source_code[..*at],
+                    stmt,
+                    &source_code[*at..]
+                ));
+                println!(
+                    "[{}] {}-{} {} (shadow) : {} -> {}",
+                    index + 1,
+                    start_point,
+                    end_point,
+                    rename.decl.kind(),
+                    rename.old,
+                    stmt.trim()
+                );
+            }
+        }
+    }
+    modified_versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // source_code에서 at byte에 있는 선언을 new로 바꾼 코드
+    fn rename_at(source_code: &str, at: usize, new: &str) -> String {
+        let tree = parse(source_code);
+        let decl = tree
+            .root_node()
+            .named_descendant_for_byte_range(at, at + 1)
+            .unwrap();
+        let (scope, kinds, from) = classify(decl).unwrap();
+        let rename = Rename {
+            decl,
+            scope,
+            kinds,
+            from,
+            old: node_text(source_code, decl).to_string(),
+        };
+        format!(
+            "{}{}{}",
+            &source_code[..scope.start_byte()],
+            rename_in_scope(source_code, &rename, new),
+            &source_code[scope.end_byte()..]
+        )
+    }
+
+    #[test]
+    fn let_rename_starts_after_the_binding() {
+        let source_code = "fn f(x: u8) { g(x); let x = x + 1; h(x); }";
+        let at = source_code.find("let x").unwrap() + 4;
+        assert_eq!(
+            rename_at(source_code, at, "y"),
+            "fn f(x: u8) { g(x); let y = x + 1; h(y); }"
+        );
+    }
+
+    #[test]
+    fn let_rename_stops_at_next_shadowing_let() {
+        let source_code = "fn f() { let x = 1; g(x); let x = x; h(x); }";
+        let at = source_code.find("let x").unwrap() + 4;
+        assert_eq!(
+            rename_at(source_code, at, "y"),
+            "fn f() { let y = 1; g(y); let x = y; h(x); }"
+        );
+    }
+
+    #[test]
+    fn inner_shadowing_ends_with_its_block() {
+        let source_code = "fn f() { let x = 1; { let x = 2; g(x); } |x: u8| x; h(x); }";
+        let at = source_code.find("let x").unwrap() + 4;
+        assert_eq!(
+            rename_at(source_code, at, "y"),
+            "fn f() { let y = 1; { let x = 2; g(x); } |x: u8| x; h(y); }"
+        );
+    }
+
+    #[test]
+    fn parameter_rename_skips_function_name() {
+        let source_code = "fn x(x: u8) -> u8 { x }";
+        let at = source_code.find("(x").unwrap() + 1;
+        assert_eq!(rename_at(source_code, at, "y"), "fn x(y: u8) -> u8 { y }");
+    }
+
+    #[test]
+    fn type_rename_keeps_values_with_same_name() {
+        let source_code = "struct S(u8);\nfn f(v: S) { let S = 1; S::g(); S(S); }";
+        let at = source_code.find('S').unwrap();
+        assert_eq!(
+            rename_at(source_code, at, "T"),
+            "struct T(u8);\nfn f(v: T) { let S = 1; T::g(); T(S); }"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::fs;
+
+use super::{is_type, mutate_with_notes, node_text};
+use crate::TypePosInfo;
+
+// type을 까다로운 type으로 바꾸는 mutator.
+// 예전 modify_types_my 는 type_identifier -> ["", "i32", "str", "Copy"] 정도의 작은 표만 있었다.
+// 여기서는 _type 에 속하는 모든 node를 사전에 있는 type으로 바꾼다.
+// 사전 항목의 {} 자리에는 원래 type이 들어간다. ([{}; 0] 은 [T; 0] 이 된다)
+
+const TYPE_DICT: [&str; 36] = [
+    "!",
+    "()",
+    "_",
+    "Self",
+    "str",
+    "[u8]",
+    "[{}]",
+    "[{}; 0]",
+    "[{}; usize::MAX]",
+    "[(); usize::MAX]",
+    "std::marker::PhantomData<{}>",
+    "dyn std::any::Any",
+    "dyn Fn() -> {}",
+    "dyn Iterator<Item = {}>",
+    "dyn Send + Sync",
+    "impl Sized",
+    "impl Fn() -> {}",
+    "impl Iterator<Item = {}>",
+    "impl ?Sized",
+    "extern \"C\" fn({}) -> {}",
+    "unsafe extern \"C\" fn(...)",
+    "extern \"rust-call\" fn(({},))",
+    "fn() -> !",
+    "*const {}",
+    "*mut {}",
+    "*const dyn Fn()",
+    "&'static mut {}",
+    "&'static dyn std::fmt::Debug",
+    "<{} as Iterator>::Item",
+    "<{} as std::ops::Deref>::Target",
+    "<Self as Iterator>::Item",
+    "Self::Output",
+    "u128",
+    "f16",
+    "char",
+    "Box<dyn FnOnce({}) -> {}>",
+];
+
+// 내장 사전에 사용자 파일의 type을 더한다. 파일은 한 줄에 type 하나, #으로 시작하는 줄은 무시한다.
+pub fn type_dictionary(dict: Option<&str>) -> Vec<String> {
+    let mut types: Vec<String> = TYPE_DICT.iter().map(|s| s.to_string()).collect();
+    if let Some(dict) = dict {
+        let text = fs::read_to_string(dict).expect("Cannot read type dictionary.");
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') && !types.iter().any(|t| t == line) {
+                types.push(line.to_string());
+            }
+        }
+    }
+    types
+}
+
+pub fn mutate_types(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    types: &[String],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_notes(source_code, structs, mutation_count, |source_code, node| {
+        if !is_type(node) {
+            return vec![];
+        }
+        let text = node_text(source_code, node);
+        types
+            .iter()
+            .map(|t| (t.replace("{}", text), String::new()))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    #[test]
+    fn types_are_replaced_from_the_dictionary() {
+        let source_code = "struct S { a: Vec<u8> }";
+        let nodes = get_splice_parts(&source_code.to_string());
+        let types = vec!["!".to_string(), "*const {}".to_string()];
+        let all = mutate_types(source_code, &nodes, &types, 0);
+        for mutant in [
+            "struct S { a: ! }",
+            "struct S { a: *const Vec<u8> }",
+            "struct S { a: Vec<!> }",
+            "struct S { a: Vec<*const u8> }",
+        ] {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+        // 선언하는 이름과 generic type의 이름 부분은 안 바꾼다
+        assert!(!all
+            .iter()
+            .any(|m| m.starts_with("struct ! ") || m.contains("!<u8>")));
+    }
+
+    #[test]
+    fn dictionary_file_adds_types() {
+        let path = std::env::temp_dir().join(format!("crate-types-{}.txt", std::process::id()));
+        fs::write(&path, "# comment\n\nu8\n  Rc<{}>  \n").unwrap();
+        let types = type_dictionary(Some(path.to_str().unwrap()));
+        assert_eq!(types.len(), TYPE_DICT.len() + 2);
+        assert_eq!(&types[TYPE_DICT.len()..], ["u8", "Rc<{}>"]);
+        assert_eq!(type_dictionary(None).len(), TYPE_DICT.len());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,259 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_gates, node_text, Replacement};
+use crate::TypePosInfo;
+
+// MIR와 codegen을 노리는 unsafe, raw pointer mutator. --profile unsafe 로 켠다.
+// 값을 raw pointer cast, 크기가 다른 transmute, MaybeUninit, union field read로 바꾸고,
+// unsafe block 안에 raw pointer 문장을, 함수 본문 맨 앞에 x86_64 asm! 을 넣고,
+// struct, enum, union 의 #[repr] 을 바꾼다.
+
+// 값 자리에 넣는 것들. {} 자리에 원래 expression이 들어간다
+const VALUE_WRAPPERS: [&str; 12] = [
+    "unsafe { {} }",
+    "unsafe { *(&{} as *const _ as *const u64) }",
+    "unsafe { *(&{} as *const _ as *mut u64) }",
+    "unsafe { *(&raw const {}) }",
+    "unsafe { core::ptr::read_unaligned((&{} as *const _ as *const u8).add(1)) }",
+    "{ let __p = &{} as *const _; unsafe { __p.add(1).read() } }",
+    "unsafe { core::mem::transmute::<_, u64>({}) }",
+    "unsafe { core::mem::transmute::<_, [u8; 3]>({}) }",
+    "unsafe { core::mem::transmute_copy::<_, u128>(&{}) }",
+    "{ let _ = {}; unsafe { core::mem::MaybeUninit::uninit().assume_init() } }",
+    "{ let _ = {}; unsafe { core::mem::zeroed() } }",
+    "{ union __U<T> { a: core::mem::ManuallyDrop<T>, b: u64 } unsafe { __U { a: core::mem::ManuallyDrop::new({}) }.b } }",
+];
+
+// unsafe block 맨 앞에 넣는 문장들
+const UNSAFE_STATEMENTS: [&str; 7] = [
+    "let __p = &0u64 as *const u64 as *mut u64; *__p = 1;",
+    "let __x: u32 = core::mem::transmute(0u64);",
+    "let __m: u8 = core::mem::MaybeUninit::uninit().assume_init();",
+    "let __x = *(1 as *const u8);",
+    "let __r = &*core::ptr::null::<u8>();",
+    "let __a = [0u8; 4]; let __x = *(__a.as_ptr().offset(-1));",
+    "let __f: fn() = core::mem::transmute(0usize);",
+];
+
+// 함수 본문 맨 앞에 넣는 x86_64 inline asm
+const ASM_STATEMENTS: [&str; 7] = [
+    "core::arch::asm!(\"nop\");",
+    "let __x: u64; core::arch::asm!(\"mov {0}, 1\", out(reg) __x);",
+    "core::arch::asm!(\"xor {0}, {0}\", inout(reg) 0u64 => _);",
+    "core::arch::asm!(\"push rax\", \"pop rax\");",
+    "core::arch::asm!(\"lea {0}, [rip]\", out(reg) _);",
+    "core::arch::asm!(\"ud2\", options(noreturn));",
+    "core::arch::asm!(\"nop\", options(pure, nomem));",
+];
+
+const STRUCT_REPRS: [(&str, &str); 8] = [
+    ("C", ""),
+    ("packed", ""),
+    ("packed(2)", ""),
+    ("align(64)", ""),
+    ("C, packed", ""),
+    ("transparent", ""),
+    ("u8", ""),
+    ("simd", "repr_simd"),
+];
+
+const ENUM_REPRS: [(&str, &str); 5] = [
+    ("u8", ""),
+    ("i128", ""),
+    ("C", ""),
+    ("C, u8", ""),
+    ("packed", ""),
+];
+
+// 값으로 쓰이는 expression인지. 함수 이름이나 macro 안의 token은 뺀다
+fn is_value(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match parent.kind() {
+        "call_expression" => return parent.child_by_field_name("function") != Some(node),
+        "field_expression" => return parent.child_by_field_name("value") == Some(node),
+        // 대입의 왼쪽은 place라서 뺀다
+        "assignment_expression" => return parent.child_by_field_name("right") == Some(node),
+        _ => {}
+    }
+    parent.kind().ends_with("_expression")
+        || matches!(parent.kind(), "arguments" | "let_declaration")
+}
+
+// block 맨 앞에 문장을 넣는다
+fn prepend(source_code: &str, block: Node, statement: &str) -> String {
+    let text = node_text(source_code, block);
+    format!("{{ {}{}", statement, &text[1..])
+}
+
+fn repr_replacements(
+    source_code: &str,
+    node: Node,
+    reprs: &[(&str, &'static str)],
+) -> Vec<Replacement> {
+    let text = node_text(source_code, node);
+    reprs
+        .iter()
+        .map(|(repr, gate)| {
+            (
+                format!("#[repr({})]\n{}", repr, text),
+                format!("add #[repr({})]", repr),
+                *gate,
+            )
+        })
+        .collect()
+}
+
+fn unsafety_replacements(source_code: &str, node: Node) -> Vec<Replacement> {
+    let text = node_text(source_code, node);
+    match node.kind() {
+        "unsafe_block" => {
+            let Some(block) = node.named_child(0) else {
+                return vec![];
+            };
+            UNSAFE_STATEMENTS
+                .iter()
+                .map(|statement| {
+                    (
+                        format!("unsafe {}", prepend(source_code, block, statement)),
+                        format!("insert {}", statement),
+                        "",
+                    )
+                })
+                .collect()
+        }
+        "block" if node.parent().map(|p| p.kind()) == Some("function_item") => ASM_STATEMENTS
+            .iter()
+            .map(|statement| {
+                (
+                    prepend(source_code, node, &format!("unsafe {{ {} }}", statement)),
+                    format!("insert {}", statement),
+                    "",
+                )
+            })
+            .collect(),
+        "struct_item" => {
+            let mut exprs = repr_replacements(source_code, node, &STRUCT_REPRS);
+            // 이름 붙은 field가 있는 struct는 union으로
+            if node.child_by_field_name("body").map(|b| b.kind()) == Some("field_declaration_list")
+            {
+                exprs.push((
+                    text.replacen("struct", "union", 1),
+                    "struct -> union".to_string(),
+                    "",
+                ));
+            }
+            exprs
+        }
+        "union_item" => {
+            let mut exprs = repr_replacements(source_code, node, &STRUCT_REPRS);
+            exprs.push((
+                text.replacen("union", "struct", 1),
+                "union -> struct".to_string(),
+                "",
+            ));
+            if let Some(body) = node.child_by_field_name("body") {
+                for field in [
+                    "__pad: [u8; 3],",
+                    "__big: [u64; 4],",
+                    "__s: core::mem::ManuallyDrop<String>,",
+                    "__s: String,",
+                ] {
+                    exprs.push((
+                        format!(
+                            "{}{{ {}{}",
+                            &source_code[node.start_byte()..body.start_byte()],
+                            field,
+                            &source_code[body.start_byte() + 1..node.end_byte()]
+                        ),
+                        format!("add field {}", field),
+                        "",
+                    ));
+                }
+            }
+            exprs
+        }
+        "enum_item" => repr_replacements(source_code, node, &ENUM_REPRS),
+        "identifier" | "field_expression" | "integer_literal" | "call_expression"
+            if is_value(node) =>
+        {
+            VALUE_WRAPPERS
+                .iter()
+                .map(|wrapper| (wrapper.replace("{}", text), format!("wrap {}", wrapper), ""))
+                .collect()
+        }
+        _ => vec![],
+    }
+}
+
+pub fn mutate_unsafety(
+    source_code: &str,
+    structs: &[TypePosInfo],
+    mutation_count: i32,
+) -> Vec<String> {
+    mutate_with_gates(source_code, structs, mutation_count, unsafety_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_unsafety(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn values_are_wrapped_but_places_and_callees_are_not() {
+        let source_code = "fn f(mut a: u8) { a = g(a); }";
+        assert_mutants(
+            source_code,
+            &[
+                "fn f(mut a: u8) { a = g(unsafe { a }); }",
+                "fn f(mut a: u8) { a = unsafe { core::mem::transmute::<_, u64>(g(a)) }; }",
+            ],
+        );
+        let all = mutants(source_code);
+        assert!(!all.iter().any(|m| m.contains("} = g(a)")));
+        assert!(!all.iter().any(|m| m.contains("unsafe { g }")));
+    }
+
+    #[test]
+    fn blocks_get_raw_pointer_statements_and_asm() {
+        assert_mutants(
+            "fn f() { unsafe { g() } }",
+            &[
+                "fn f() { unsafe { let __x = *(1 as *const u8); g() } }",
+                "fn f() { unsafe { core::arch::asm!(\"nop\"); } unsafe { g() } }",
+            ],
+        );
+    }
+
+    #[test]
+    fn reprs_and_unions() {
+        assert_mutants(
+            "struct S { a: u8 }",
+            &[
+                "#[repr(packed(2))]\nstruct S { a: u8 }",
+                "#![feature(repr_simd)]\n#[repr(simd)]\nstruct S { a: u8 }",
+                "union S { a: u8 }",
+            ],
+        );
+        // tuple struct는 union이 될 수 없다
+        assert!(!mutants("struct T(u8);").iter().any(|m| m.contains("union")));
+        assert_mutants(
+            "union U { a: u8 }",
+            &["struct U { a: u8 }", "union U { __s: String, a: u8 }"],
+        );
+        assert_mutants("enum E { A }", &["#[repr(i128)]\nenum E { A }"]);
+    }
+}
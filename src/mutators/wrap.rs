@@ -0,0 +1,184 @@
+use tree_sitter::Node;
+
+use super::{mutate_with_notes, node_text};
+use crate::TypePosInfo;
+
+// expression과 block을 새 구조로 감싸거나, 반대로 자식 하나만 남기고 벗겨내는 mutator.
+// 감싼 결과는 문법적으로 맞아서 parser를 넘어 type check, MIR build, async lowering까지 간다.
+// 벗겨내기(hoist)는 mutate_delete_only의 반대쪽이다. 지우는 대신 node를 자식으로 바꾼다.
+
+// {} 자리에 원래 expression이 들어간다
+const EXPR_WRAPPERS: [&str; 16] = [
+    "unsafe { {} }",
+    "async { {} }",
+    "async move { {} }",
+    "(async move || {})",
+    "const { {} }",
+    "loop { break {} }",
+    "(|| {})()",
+    "(move || {})()",
+    "Box::new({})",
+    "Some({})",
+    "&{}",
+    "&mut {}",
+    "({})?",
+    "Some({})?",
+    "Ok({})?",
+    "({}).await",
+];
+
+// block은 block 자리에 있어야 하니 { } 로 한 번 더 감싼다
+const BLOCK_WRAPPERS: [&str; 6] = [
+    "{ unsafe {} }",
+    "{ async {}.await }",
+    "{ const {} }",
+    "{ loop { break {} } }",
+    "{ (|| {})() }",
+    "{ Box::new({}) }",
+];
+
+// 다른 node 안에 들어있는 것을 꺼내도 되는 type node
+const TYPE_KINDS: [&str; 6] = [
+    "generic_type",
+    "reference_type",
+    "pointer_type",
+    "array_type",
+    "tuple_type",
+    "function_type",
+];
+
+fn is_expression(node: Node) -> bool {
+    let kind = node.kind();
+    // unsafe_block, async_block, const_block 도 expression이다
+    if kind.ends_with("_expression") || (kind.ends_with("_block") && kind != "block") {
+        return true;
+    }
+    // identifier와 literal은 pattern이나 선언 자리에도 나오니 expression 안이나 block의 끝 값일 때만
+    (kind == "identifier" || kind.ends_with("_literal"))
+        && node
+            .parent()
+            .map(|p| p.kind().ends_with("_expression") || matches!(p.kind(), "arguments" | "block"))
+            .unwrap_or(false)
+}
+
+// 함수 본문이나 if의 block이 아니라, 값으로 쓰이는 block인지
+fn is_block_expression(node: Node) -> bool {
+    matches!(
+        node.parent().map(|p| p.kind()),
+        Some("expression_statement") | Some("let_declaration") | Some("arguments") | Some("block")
+    )
+}
+
+fn wrap(wrapper: &str, text: &str) -> String {
+    let (open, close) = wrapper.split_once("{}").unwrap();
+    format!("{}{}{}", open, text, close)
+}
+
+// 바꿔 넣을 수 있는 자식들: expression이면 expression과 block, type이면 type
+fn hoistable_children(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    let mut out = vec![];
+    for child in node.named_children(&mut cursor) {
+        if is_expression(node) && (is_expression(child) || child.kind() == "block") {
+            out.push(child);
+        } else if TYPE_KINDS.contains(&node.kind()) {
+            if child.kind() == "type_arguments" {
+                let mut args = child.walk();
+                out.extend(
+                    child
+                        .named_children(&mut args)
+                        .filter(|a| a.kind().ends_with("type") || a.kind() == "type_identifier"),
+                );
+            } else if child.kind().ends_with("type") || child.kind() == "type_identifier" {
+                out.push(child);
+            }
+        } else if node.kind() == "block" && is_expression(child) && is_block_expression(node) {
+            // { x } -> x
+            out.push(child);
+        }
+    }
+    out
+}
+
+fn wrap_replacements(source_code: &str, node: Node) -> Vec<(String, String)> {
+    let text = node_text(source_code, node);
+    let mut exprs = vec![];
+    if is_expression(node) {
+        for wrapper in EXPR_WRAPPERS {
+            exprs.push((wrap(wrapper, text), format!("wrap {}", wrapper)));
+        }
+    } else if node.kind() == "block" {
+        for wrapper in BLOCK_WRAPPERS {
+            exprs.push((wrap(wrapper, text), format!("wrap {}", wrapper)));
+        }
+    }
+    for child in hoistable_children(node) {
+        exprs.push((
+            node_text(source_code, child).to_string(),
+            format!("hoist {}", child.kind()),
+        ));
+    }
+    exprs
+}
+
+pub fn mutate_wrap(source_code: &str, structs: &[TypePosInfo], mutation_count: i32) -> Vec<String> {
+    mutate_with_notes(source_code, structs, mutation_count, wrap_replacements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_splice_parts;
+
+    fn mutants(source_code: &str) -> Vec<String> {
+        let nodes = get_splice_parts(&source_code.to_string());
+        mutate_wrap(source_code, &nodes, 0)
+    }
+
+    fn assert_mutants(source_code: &str, expected: &[&str]) {
+        let all = mutants(source_code);
+        for mutant in expected {
+            assert!(all.iter().any(|m| m == mutant), "{}", mutant);
+        }
+    }
+
+    #[test]
+    fn expressions_are_wrapped_and_hoisted() {
+        assert_mutants(
+            "fn f(a: u8) { g(a + 1); }",
+            &[
+                "fn f(a: u8) { g(unsafe { a + 1 }); }",
+                "fn f(a: u8) { g((|| a + 1)()); }",
+                "fn f(a: u8) { g(Some(a + 1)?); }",
+                "fn f(a: u8) { g(a); }",
+                "fn f(a: u8) { g(1); }",
+                "fn f(a: u8) { g((a + 1).await); }",
+            ],
+        );
+    }
+
+    #[test]
+    fn value_blocks_are_wrapped_and_unwrapped() {
+        assert_mutants(
+            "fn f() { let x = { 1 }; }",
+            &[
+                "fn f() { let x = { const { 1 } }; }",
+                "fn f() { let x = 1; }",
+            ],
+        );
+        // 함수 본문은 벗겨내지 않는다
+        assert!(!mutants("fn f() { 1 }").contains(&"fn f() 1".to_string()));
+    }
+
+    #[test]
+    fn types_are_hoisted() {
+        assert_mutants(
+            "fn f(x: &Vec<Option<u8>>) {}",
+            &[
+                "fn f(x: Vec<Option<u8>>) {}",
+                "fn f(x: &Option<u8>) {}",
+                "fn f(x: &Vec<u8>) {}",
+            ],
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use tree_sitter::Point;
+
+use crate::mutators::parse;
+
+// coverage instrumentation 없이 쓸 수 있는 feedback: rustc가 낸 진단의 종류.
+// 진단 하나의 이름은 error code (E0308), lint 이름 (unused_variables),
+// 둘 다 없으면 `..` 안과 숫자를 지운 메시지 ("error: `_` attribute cannot be used on statements").
+// 진단의 위치(--> 줄)에 있는 node 종류를 같이 돌려줘서 (진단, node 종류) 쌍도 셀 수 있게 한다.
+
+// 진단이 아니라 요약인 줄들
+const SUMMARIES: [&str; 5] = [
+    "aborting due to",
+    "emitted",
+    "could not compile",
+    "internal compiler error",
+    "Some errors have detailed explanations",
+];
+
+struct Raw {
+    // error code 또는 lint 이름
+    name: Option<String>,
+    // "warning: unused variable: `_`"
+    message: String,
+    point: Option<Point>,
+}
+
+// 숫자를 지우고 `..` 안은 `_` 로 바꿔서 같은 종류의 진단이 같은 문자열이 되게 한다
+fn normalize(message: &str) -> String {
+    let mut out = String::new();
+    for (i, part) in message.split('`').enumerate() {
+        if i % 2 == 1 {
+            out.push_str("`_`");
+        } else {
+            out.extend(part.chars().filter(|c| !c.is_ascii_digit()));
+        }
+    }
+    out.trim().to_string()
+}
+
+// "error[E0308]: mismatched types" -> (Some("E0308"), "error: mismatched types")
+fn header(line: &str) -> Option<(Option<String>, String)> {
+    let severity = if line.starts_with("error") {
+        "error"
+    } else if line.starts_with("warning") {
+        "warning"
+    } else {
+        return None;
+    };
+    let (head, message) = line.split_once(": ")?;
+    if SUMMARIES.iter().any(|s| line.contains(s)) {
+        return None;
+    }
+    let code = head
+        .strip_prefix(severity)?
+        .strip_prefix('[')
+        .and_then(|c| c.strip_suffix(']'))
+        .map(str::to_string);
+    if code.is_none() && head != severity {
+        return None;
+    }
+    Some((code, format!("{}: {}", severity, normalize(message))))
+}
+
+// "= note: `#[warn(unused_variables)]` (part of ..) on by default" -> "unused_variables"
+fn lint_name(line: &str) -> Option<String> {
+    let note = line.trim_start().strip_prefix("= note: `#[")?;
+    let (_, rest) = note.split_once('(')?;
+    let (name, _) = rest.split_once(')')?;
+    Some(name.to_string())
+}
+
+// " --> src/mut_a.rs_1.rs:3:9" 에서 file_name 파일의 위치 (rustc는 1부터, column은 글자 단위)
+fn location(line: &str, file_name: &str, source_code: &str) -> Option<Point> {
+    let rest = line.trim_start().strip_prefix("--> ")?;
+    let pos = rest.find(file_name)?;
+    let mut parts = rest[pos + file_name.len()..].split(':').skip(1);
+    let row = parts.next()?.trim().parse::<usize>().ok()?.checked_sub(1)?;
+    let column = parts.next()?.trim().parse::<usize>().ok()?.checked_sub(1)?;
+    let text = source_code.lines().nth(row)?;
+    let column = text
+        .char_indices()
+        .nth(column)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    Some(Point::new(row, column))
+}
+
+pub fn collect(
+    stderr: &str,
+    source_code: &str,
+    file_name: &str,
+) -> Vec<(String, Option<&'static str>)> {
+    let mut raws: Vec<Raw> = vec![];
+    for line in stderr.lines() {
+        if let Some((name, message)) = header(line) {
+            raws.push(Raw {
+                name,
+                message,
+                point: None,
+            });
+            continue;
+        }
+        let Some(raw) = raws.last_mut() else {
+            continue;
+        };
+        if raw.point.is_none() {
+            raw.point = location(line, file_name, source_code);
+        }
+        if raw.name.is_none() {
+            raw.name = lint_name(line);
+        }
+    }
+
+    // lint 이름은 같은 lint의 첫 warning에만 붙으니, 같은 메시지의 나머지에도 붙여준다
+    let lints: HashMap<String, String> = raws
+        .iter()
+        .filter_map(|r| Some((r.message.clone(), r.name.clone()?)))
+        .collect();
+    let tree = raws
+        .iter()
+        .any(|r| r.point.is_some())
+        .then(|| parse(source_code));
+    raws.into_iter()
+        .map(|raw| {
+            let kind = match (&tree, raw.point) {
+                (Some(tree), Some(point)) => tree
+                    .root_node()
+                    .named_descendant_for_point_range(point, point)
+                    .map(|n| n.kind()),
+                _ => None,
+            };
+            let name = raw
+                .name
+                .or_else(|| lints.get(&raw.message).cloned())
+                .unwrap_or(raw.message);
+            (name, kind)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {
+    let x: u8 = \"a\";
+    let y = 1;
+    let z = 2;
+}
+";
+
+    const STDERR: &str = "error[E0308]: mismatched types
+ --> out/mut_a.rs_1.rs:2:17
+  |
+2 |     let x: u8 = \"a\";
+  |            --   ^^^ expected `u8`, found `&str`
+
+warning: unused variable: `y`
+ --> out/mut_a.rs_1.rs:3:9
+  |
+3 |     let y = 1;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_y`
+  |
+  = note: `#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default
+
+warning: unused variable: `z`
+ --> out/mut_a.rs_1.rs:4:9
+
+error: `#[inline]` attribute cannot be used on statements 12
+ --> other.rs:1:1
+
+error: aborting due to 2 previous errors; 2 warnings emitted
+
+Some errors have detailed explanations: E0308.
+";
+
+    #[test]
+    fn collect_names_and_node_kinds() {
+        let diagnostics = collect(STDERR, SOURCE, "mut_a.rs_1.rs");
+        assert_eq!(
+            diagnostics,
+            [
+                ("E0308".to_string(), Some("string_literal")),
+                ("unused_variables".to_string(), Some("identifier")),
+                ("unused_variables".to_string(), Some("identifier")),
+                (
+                    "error: `_` attribute cannot be used on statements".to_string(),
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn summaries_are_not_diagnostics() {
+        assert!(header("error: aborting due to 2 previous errors").is_none());
+        assert!(header("warning: 1 warning emitted").is_none());
+        assert!(header("error: could not compile `a` (bin \"a\")").is_none());
+        assert!(header("errors: not a header").is_none());
+        assert_eq!(
+            header("warning[E0170]: pattern binding `A` is named the same as a variant 3"),
+            Some((
+                Some("E0170".to_string()),
+                "warning: pattern binding `_` is named the same as a variant".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn location_counts_characters() {
+        let source = "fn main() { let s = \"한글\"; let t = 1; }";
+        let point = location(" --> a.rs:1:27", "a.rs", source).unwrap();
+        assert_eq!(&source[point.column..point.column + 3], "let");
+        assert!(location(" --> b.rs:1:1", "a.rs", source).is_none());
+        assert!(location(" --> a.rs:9:1", "a.rs", source).is_none());
+    }
+}
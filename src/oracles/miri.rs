@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use tree_sitter::Point;
+
+use super::rustc::Compiled;
+use super::{Finding, OracleConfig};
+use crate::runner;
+
+// rustc가 받아준 실행 가능한 mutant를 miri로 돌린다.
+// miri는 rustc driver라서 rustc와 같은 인자를 받고, sysroot는 MIRI_SYSROOT로 넘긴다.
+// (cargo miri setup 을 한 번 돌리면 sysroot 경로를 알려준다)
+fn run_miri(config: &OracleConfig, source_path: &Path) -> runner::RunResult {
+    let mut command = Command::new(&config.miri);
+    command
+        .env("RUSTC_BOOTSTRAP", "1")
+        .arg("--edition=2021")
+        .arg("--crate-name=mutant")
+        .arg("--crate-type=bin")
+        .arg(source_path);
+    if let Some(sysroot) = &config.miri_sysroot {
+        command.env("MIRI_SYSROOT", sysroot);
+    }
+    runner::run_with_timeout(command, config.timeout)
+}
+
+// stderr에서 mutant 파일을 가리키는 첫 번째 위치(path:line:col)를 찾는다.
+// UB가 std 안에서 났더라도 "inside `main` at ..." 같은 backtrace에 mutant 위치가 나온다.
+fn first_location(stderr: &str, file_name: &str) -> Option<Point> {
+    for line in stderr.lines() {
+        if let Some(pos) = line.find(file_name) {
+            let mut rest = line[pos + file_name.len()..].split(':').skip(1);
+            let row = rest.next()?.trim().parse::<usize>().ok()?;
+            let column: String = rest
+                .next()?
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            let column = column.parse::<usize>().ok()?;
+            return Some(Point::new(row.saturating_sub(1), column.saturating_sub(1)));
+        }
+    }
+    None
+}
+
+// 해당 위치가 unsafe block 이나 unsafe fn 안에 있는지 본다.
+fn is_in_unsafe(source_code: &str, point: Point) -> bool {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_rust::language()).unwrap();
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut node = tree.root_node().descendant_for_point_range(point, point);
+    while let Some(n) = node {
+        match n.kind() {
+            "unsafe_block" => return true,
+            "function_item" => {
+                let mut cursor = n.walk();
+                let unsafe_fn = n.children(&mut cursor).any(|c| {
+                    c.kind() == "function_modifiers"
+                        && source_code[c.start_byte()..c.end_byte()].contains("unsafe")
+                });
+                if unsafe_fn {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        node = n.parent();
+    }
+    false
+}
+
+fn ub_message(stderr: &str) -> String {
+    let line = stderr
+        .lines()
+        .find(|l| l.contains("Undefined Behavior"))
+        .unwrap_or("");
+    line.chars()
+        .filter(|c| !c.is_ascii_digit())
+        .take(120)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+// 세 가지를 구분해서 bucket을 나눈다.
+// - safe-ub: unsafe 밖의 코드에서 UB. safe code만으로 UB가 나면 soundness 버그다.
+// - unsafe-ub: unsafe 안에서 난 UB. mutant가 만든 잘못된 unsafe 코드일 가능성이 높다.
+// - internal-error: miri 자체의 ICE, crash.
+// 프로그램의 panic, unsupported operation, timeout은 버그가 아니므로 무시한다.
+pub fn check(config: &OracleConfig, mutant_path: &Path, base: &Compiled) -> Vec<Finding> {
+    if base.binary.is_none() {
+        return vec![];
+    }
+    let run = run_miri(config, mutant_path);
+    let artifacts = vec![("miri.stderr".to_string(), run.stderr.clone())];
+
+    if runner::is_ice(&run.stderr) || run.verdict == runner::Verdict::Crash {
+        return vec![Finding {
+            oracle: "miri",
+            kind: "internal-error",
+            signature: runner::ice_signature(&run.stderr),
+            artifacts,
+        }];
+    }
+    if !run.stderr.contains("Undefined Behavior") {
+        return vec![];
+    }
+
+    let source_code = fs::read_to_string(mutant_path).unwrap_or_default();
+    let file_name = mutant_path.file_name().unwrap().to_string_lossy();
+    // unsafe 라는 글자가 아예 없으면 위치를 볼 것도 없이 safe code다
+    let in_unsafe = source_code.contains("unsafe")
+        && match first_location(&run.stderr, &file_name) {
+            Some(point) => is_in_unsafe(&source_code, point),
+            // 위치를 모르면 soundness 버그로 잘못 분류하지 않도록 unsafe 쪽으로 본다
+            None => true,
+        };
+    vec![Finding {
+        oracle: "miri",
+        kind: if in_unsafe { "unsafe-ub" } else { "safe-ub" },
+        signature: ub_message(&run.stderr),
+        artifacts,
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "fn main() {
+    let x = 1;
+    unsafe {
+        let p = 8 as *const u8;
+        let _ = *p;
+    }
+}
+
+unsafe fn f(p: *const u8) -> u8 {
+    *p
+}
+";
+
+    const STDERR: &str = "error: Undefined Behavior: dereferencing pointer failed: 0x8[noalloc] is a dangling pointer (it has no provenance)
+ --> out/mut_a.rs_3.rs:5:17
+  |
+5 |         let _ = *p;
+  |                 ^^ Undefined Behavior occurred here
+  |
+  = note: BACKTRACE:
+  = note: inside `main` at out/mut_a.rs_3.rs:5:17: 5:19
+";
+
+    #[test]
+    fn first_location_finds_the_mutant_position() {
+        assert_eq!(
+            first_location(STDERR, "mut_a.rs_3.rs"),
+            Some(Point::new(4, 16))
+        );
+        assert_eq!(first_location(STDERR, "mut_b.rs_3.rs"), None);
+    }
+
+    #[test]
+    fn unsafe_blocks_and_unsafe_fns_are_unsafe() {
+        assert!(is_in_unsafe(SOURCE, Point::new(4, 16)));
+        assert!(is_in_unsafe(SOURCE, Point::new(9, 4)));
+        assert!(!is_in_unsafe(SOURCE, Point::new(1, 8)));
+    }
+
+    #[test]
+    fn ub_message_drops_numbers() {
+        assert_eq!(
+            ub_message(STDERR),
+            "error: Undefined Behavior: dereferencing pointer failed: x[noalloc] is a dangling pointer (it has no provenance)"
+        );
+    }
+}
@@ -0,0 +1,325 @@
+pub mod diagnostics;
+pub mod miri;
+pub mod opt;
+pub mod pretty;
+pub mod rustc;
+pub mod rustfmt;
+pub mod solver;
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::runner;
+
+// 지금 지원하는 oracle 이름들. --oracle 에 쉼표로 구분해서 넘긴다.
+pub const ORACLES: [&str; 6] = ["rustc", "pretty", "rustfmt", "miri", "solver", "opt"];
+
+pub struct OracleConfig {
+    pub oracles: Vec<String>,
+    pub rustc: String,
+    pub rustfmt: String,
+    pub miri: String,
+    // 없으면 miri가 알아서 찾게 둔다
+    pub miri_sysroot: Option<String>,
+    // solver oracle이 -Zpolonius 도 같이 비교할지
+    pub solver_polonius: bool,
+    pub timeout: u64,
+    // rustc를 부를 때마다 더 넘기는 인자. --profile 이 채운다
+    pub rustc_args: Vec<String>,
+    // oracle이 중간 결과물(컴파일 결과, pretty print 결과 등)을 쓰는 곳
+    pub work_dir: PathBuf,
+    // bucket 별로 finding을 저장하는 곳
+    pub crash_dir: PathBuf,
+    // 처음 보는 진단이나 (진단, node 종류) 쌍을 만든 mutant를 모아두는 곳
+    pub corpus_dir: PathBuf,
+}
+
+impl OracleConfig {
+    // 도구 경로와 timeout은 기본값으로 채우고, 필요하면 만든 다음에 바꾼다.
+    pub fn new(oracles: Vec<String>, output_dir: &Path) -> Self {
+        for name in oracles.iter() {
+            if !ORACLES.contains(&name.as_str()) {
+                panic!("No such oracle: {}", name);
+            }
+        }
+        let work_dir = output_dir.join("work");
+        let crash_dir = output_dir.join("crashes");
+        let corpus_dir = output_dir.join("corpus");
+        if !oracles.is_empty() {
+            fs::create_dir_all(&work_dir).unwrap();
+            fs::create_dir_all(&crash_dir).unwrap();
+            fs::create_dir_all(&corpus_dir).unwrap();
+        }
+        OracleConfig {
+            oracles,
+            rustc: "rustc".to_string(),
+            rustfmt: "rustfmt".to_string(),
+            miri: "miri".to_string(),
+            miri_sysroot: None,
+            solver_polonius: false,
+            timeout: 10,
+            rustc_args: vec![],
+            work_dir,
+            crash_dir,
+            corpus_dir,
+        }
+    }
+}
+
+// oracle이 찾아낸 이상한 결과 하나.
+// artifacts는 mutant와 같이 저장할 (파일 이름, 내용) 목록. stderr, pretty print 결과 등.
+pub struct Finding {
+    pub oracle: &'static str,
+    pub kind: &'static str,
+    pub signature: String,
+    pub artifacts: Vec<(String, String)>,
+}
+
+#[derive(Default)]
+pub struct OracleState {
+    pub executions: usize,
+    pub accepted: usize,
+    // "oracle/kind-hash" -> 지금까지 몇 번 나왔는지
+    pub buckets: HashMap<String, usize>,
+    // 기본 컴파일에서 지금까지 본 진단 (error code, lint 이름 등) -> 나온 mutant 수
+    pub diagnostics: HashMap<String, usize>,
+    // 지금까지 본 (진단, 진단 위치의 node 종류)
+    pub diagnostic_kinds: HashSet<(String, &'static str)>,
+}
+
+impl OracleState {
+    // 같은 bucket은 처음 나온 mutant만 저장하고, 이후로는 횟수만 센다.
+    // 새 bucket이면 true.
+    pub fn record(&mut self, config: &OracleConfig, mutant_path: &Path, finding: &Finding) -> bool {
+        let bucket = format!(
+            "{}/{}-{}",
+            finding.oracle,
+            finding.kind,
+            runner::bucket_id(&finding.signature)
+        );
+        let count = self.buckets.entry(bucket.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            return false;
+        }
+
+        let bucket_dir = config.crash_dir.join(&bucket);
+        fs::create_dir_all(&bucket_dir).unwrap();
+        let file_name = mutant_path.file_name().unwrap();
+        fs::copy(mutant_path, bucket_dir.join(file_name)).unwrap();
+        fs::write(bucket_dir.join("signature.txt"), &finding.signature).unwrap();
+        for (name, contents) in finding.artifacts.iter() {
+            fs::write(bucket_dir.join(name), contents).unwrap();
+        }
+        println!(
+            "[{}] new bucket {} : {}",
+            finding.oracle, bucket, finding.signature
+        );
+        true
+    }
+
+    pub fn print_summary(&self) {
+        println!("Executions: {}", self.executions);
+        println!("Accepted by rustc: {}", self.accepted);
+        println!("Buckets: {}", self.buckets.len());
+        let mut buckets: Vec<(&String, &usize)> = self.buckets.iter().collect();
+        buckets.sort();
+        for (bucket, count) in buckets {
+            println!("  {} : {}", bucket, count);
+        }
+        self.print_diagnostics();
+    }
+
+    // 진단 coverage. error code, lint, 나머지 메시지로 나눠서 나온 mutant 수와 같이 보여준다
+    fn print_diagnostics(&self) {
+        let mut diagnostics: Vec<(&String, &usize)> = self.diagnostics.iter().collect();
+        diagnostics.sort();
+        let is_code = |d: &str| d.starts_with('E') && d[1..].chars().all(|c| c.is_ascii_digit());
+        let (codes, rest): (Vec<_>, Vec<_>) =
+            diagnostics.into_iter().partition(|(d, _)| is_code(d));
+        let (messages, lints): (Vec<_>, Vec<_>) =
+            rest.into_iter().partition(|(d, _)| d.contains(": "));
+        println!(
+            "Diagnostics: {} (error codes: {}, lints: {}, other: {}), with node kinds: {}",
+            self.diagnostics.len(),
+            codes.len(),
+            lints.len(),
+            messages.len(),
+            self.diagnostic_kinds.len()
+        );
+        for (name, group) in [("error codes", codes), ("lints", lints)] {
+            if !group.is_empty() {
+                let items: Vec<String> =
+                    group.iter().map(|(d, n)| format!("{} x{}", d, n)).collect();
+                println!("  {} : {}", name, items.join(", "));
+            }
+        }
+        for (message, count) in messages {
+            println!("  {} x{}", message, count);
+        }
+    }
+}
+
+// mutant 하나를 oracle에 돌린 결과. scheduler와 seed queue가 보상으로 쓴다
+#[derive(Default)]
+pub struct Feedback {
+    pub accepted: bool,
+    // 이 mutant가 처음 만든 bucket 수
+    pub new_buckets: usize,
+    // 이 mutant에서 처음 본 진단 수
+    pub new_diagnostics: usize,
+    // 이 mutant에서 처음 본 (진단, node 종류) 쌍 수
+    pub new_pairs: usize,
+}
+
+impl Feedback {
+    pub fn interesting(&self) -> bool {
+        self.new_buckets > 0 || self.new_diagnostics > 0 || self.new_pairs > 0
+    }
+}
+
+// 파일로 쓴 mutant 하나에 대해 켜진 oracle을 전부 돌린다.
+// 모든 oracle의 기준이 되는 건 rustc로 한 번 컴파일한 결과다.
+pub fn check_mutant(
+    config: &OracleConfig,
+    state: &mut OracleState,
+    mutant_path: &Path,
+) -> Feedback {
+    let mut feedback = Feedback::default();
+    if config.oracles.is_empty() {
+        return feedback;
+    }
+    let base = rustc::compile(config, mutant_path, "base", &[]);
+    state.executions += 1;
+    if base.run.verdict == runner::Verdict::Accepted {
+        state.accepted += 1;
+        feedback.accepted = true;
+    }
+    let source_code = fs::read_to_string(mutant_path).unwrap_or_default();
+    let file_name = mutant_path.file_name().unwrap().to_string_lossy();
+    let mut novel: Vec<String> = vec![];
+    let mut seen: HashSet<String> = HashSet::new();
+    for (diagnostic, kind) in diagnostics::collect(&base.run.stderr, &source_code, &file_name) {
+        if let Some(kind) = kind {
+            if state.diagnostic_kinds.insert((diagnostic.clone(), kind)) {
+                feedback.new_pairs += 1;
+                novel.push(format!("({}, {})", diagnostic, kind));
+            }
+        }
+        // 한 mutant에서 같은 진단이 여러 번 나와도 한 번만 센다
+        if !seen.insert(diagnostic.clone()) {
+            continue;
+        }
+        let count = state.diagnostics.entry(diagnostic.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 {
+            feedback.new_diagnostics += 1;
+            novel.push(diagnostic);
+        }
+    }
+    // 새 진단을 낸 mutant는 corpus에 남긴다
+    if !novel.is_empty() {
+        fs::copy(mutant_path, config.corpus_dir.join(&*file_name)).unwrap();
+        println!("[corpus] {} : {}", file_name, novel.join(", "));
+    }
+
+    let mut findings: Vec<Finding> = vec![];
+    for name in config.oracles.iter() {
+        match name.as_str() {
+            "rustc" => findings.append(&mut rustc::check(&base)),
+            "pretty" => findings.append(&mut pretty::check(config, mutant_path, &base)),
+            "rustfmt" => findings.append(&mut rustfmt::check(config, mutant_path, &base)),
+            "miri" => findings.append(&mut miri::check(config, mutant_path, &base)),
+            "solver" => findings.append(&mut solver::check(config, mutant_path, &base)),
+            "opt" => findings.append(&mut opt::check(config, mutant_path, &base)),
+            _ => {}
+        }
+    }
+    for finding in findings.iter() {
+        if state.record(config, mutant_path, finding) {
+            feedback.new_buckets += 1;
+        }
+    }
+    feedback
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(signature: &str) -> Finding {
+        Finding {
+            oracle: "rustc",
+            kind: "ice",
+            signature: signature.to_string(),
+            artifacts: vec![("rustc.stderr".to_string(), "stderr".to_string())],
+        }
+    }
+
+    #[test]
+    fn record_saves_the_first_mutant_of_each_bucket() {
+        let output_dir = std::env::temp_dir().join(format!("crate-record-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        let first = output_dir.join("mut_1.rs");
+        let second = output_dir.join("mut_2.rs");
+        fs::write(&first, "fn main() {}").unwrap();
+        fs::write(&second, "fn main() { }").unwrap();
+
+        let mut state = OracleState::default();
+        assert!(state.record(&config, &first, &finding("a")));
+        assert!(!state.record(&config, &second, &finding("a")));
+        assert!(state.record(&config, &second, &finding("b")));
+
+        let bucket = config
+            .crash_dir
+            .join(format!("rustc/ice-{}", runner::bucket_id("a")));
+        assert!(bucket.join("mut_1.rs").exists());
+        assert!(!bucket.join("mut_2.rs").exists());
+        assert_eq!(
+            fs::read_to_string(bucket.join("signature.txt")).unwrap(),
+            "a"
+        );
+        assert_eq!(
+            fs::read_to_string(bucket.join("rustc.stderr")).unwrap(),
+            "stderr"
+        );
+        assert_eq!(state.buckets.len(), 2);
+        assert_eq!(state.buckets.values().sum::<usize>(), 3);
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn check_mutant_reports_new_diagnostics_once() {
+        let output_dir =
+            std::env::temp_dir().join(format!("crate-feedback-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        let mut state = OracleState::default();
+        let mismatched = "fn main() { let a: u8 = \"a\"; let b: u8 = \"b\"; }";
+        let paths: Vec<PathBuf> = ["mut_1.rs", "mut_2.rs", "mut_3.rs"]
+            .iter()
+            .map(|name| output_dir.join(name))
+            .collect();
+        fs::write(&paths[0], mismatched).unwrap();
+        fs::write(&paths[1], mismatched).unwrap();
+        fs::write(&paths[2], "fn main() {}").unwrap();
+
+        let feedback = check_mutant(&config, &mut state, &paths[0]);
+        assert!(!feedback.accepted);
+        assert_eq!(feedback.new_diagnostics, 1);
+        assert_eq!(feedback.new_pairs, 1);
+        assert!(feedback.interesting());
+        assert!(config.corpus_dir.join("mut_1.rs").exists());
+
+        let feedback = check_mutant(&config, &mut state, &paths[1]);
+        assert!(!feedback.interesting());
+        assert!(!config.corpus_dir.join("mut_2.rs").exists());
+        assert_eq!(state.diagnostics["E0308"], 2);
+
+        let feedback = check_mutant(&config, &mut state, &paths[2]);
+        assert!(feedback.accepted);
+        assert!(!feedback.interesting());
+        assert_eq!((state.executions, state.accepted), (3, 1));
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
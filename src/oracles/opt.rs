@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use super::rustc::{self, Compiled};
+use super::{Finding, OracleConfig};
+use crate::runner::{self, Verdict};
+
+// 최적화 수준만 바꿔 다시 컴파일한 실행파일의 출력과 종료 상태를 -Copt-level=0 으로 컴파일한 결과와 비교한다.
+// 같은 프로그램이니 출력이 달라지면 miscompilation이다.
+// UB가 있는 mutant는 원래 결과가 달라질 수 있으니, panic과 UB가 없는 mode 22 (typed generator) 와 같이 쓴다.
+
+// 기준과 모든 variant에 같이 붙인다. opt-level에 따라 overflow check와 debug assertion이
+// 켜지고 꺼지면 overflow 하나만으로 exit이 달라지기 때문에 둘 다 켜서 고정한다.
+const COMMON: [&str; 2] = ["-Coverflow-checks=on", "-Cdebug-assertions=on"];
+
+// --profile 의 rustc_args 보다 뒤에 붙으므로 opt-level과 mir-opt-level을 둘 다 적어서 덮어쓴다
+const BASE: (&str, &[&str]) = ("opt0", &["-Copt-level=0", "-Zmir-opt-level=1"]);
+
+const VARIANTS: [(&str, &[&str]); 2] = [
+    ("opt3", &["-Copt-level=3", "-Zmir-opt-level=2"]),
+    ("mir-opt4", &["-Copt-level=0", "-Zmir-opt-level=4"]),
+];
+
+fn compile(config: &OracleConfig, mutant_path: &Path, name: &str, flags: &[&str]) -> Compiled {
+    let args: Vec<&str> = COMMON.iter().chain(flags.iter()).copied().collect();
+    rustc::compile(config, mutant_path, name, &args)
+}
+
+pub fn check(config: &OracleConfig, mutant_path: &Path, base: &Compiled) -> Vec<Finding> {
+    if base.binary.is_none() {
+        return vec![];
+    }
+    // 기준 빌드가 안 되면 비교할 게 없다
+    let opt0 = compile(config, mutant_path, BASE.0, BASE.1);
+    let Some(opt0_binary) = &opt0.binary else {
+        return vec![];
+    };
+    let expected = runner::run_binary(opt0_binary, config.timeout);
+    if expected.verdict == Verdict::Timeout {
+        return vec![];
+    }
+
+    let mut findings = vec![];
+    for (name, flags) in VARIANTS {
+        let other = compile(config, mutant_path, name, flags);
+        let (kind, signature, mut artifacts) = match other.run.verdict {
+            // opt0은 컴파일됐는데 최적화를 켜니 죽는 경우
+            Verdict::Ice | Verdict::Crash => (
+                "ice-opt-only",
+                format!("{} {}", name, runner::ice_signature(&other.run.stderr)),
+                vec![(format!("{}.stderr", name), other.run.stderr)],
+            ),
+            Verdict::Rejected => (
+                "rejected-opt-only",
+                format!("{} {}", name, runner::first_error(&other.run.stderr)),
+                vec![(format!("{}.stderr", name), other.run.stderr)],
+            ),
+            Verdict::Timeout => continue,
+            Verdict::Accepted => {
+                let Some(binary) = &other.binary else {
+                    continue;
+                };
+                let actual = runner::run_binary(binary, config.timeout);
+                if actual.verdict == Verdict::Timeout {
+                    continue;
+                }
+                let kind = if expected.verdict != actual.verdict {
+                    "exit"
+                } else if expected.stdout != actual.stdout {
+                    "output"
+                } else {
+                    continue;
+                };
+                (
+                    kind,
+                    format!("{} {:?} -> {:?}", name, expected.verdict, actual.verdict),
+                    vec![
+                        (format!("{}.stdout", BASE.0), expected.stdout.clone()),
+                        (format!("{}.stdout", name), actual.stdout),
+                    ],
+                )
+            }
+        };
+        artifacts.push((format!("{}.stderr", BASE.0), opt0.run.stderr.clone()));
+        findings.push(Finding {
+            oracle: "opt",
+            kind,
+            signature,
+            artifacts,
+        });
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn check_source(name: &str, source_code: &str) -> Vec<Finding> {
+        let output_dir =
+            std::env::temp_dir().join(format!("crate-opt-{}-{}", name, std::process::id()));
+        let config = OracleConfig::new(vec!["opt".to_string()], &output_dir);
+        let path = output_dir.join("mutant.rs");
+        fs::write(&path, source_code).unwrap();
+        let base = rustc::compile(&config, &path, "base", &[]);
+        assert_eq!(base.binary.is_some(), rustc::is_executable(source_code));
+        let findings = check(&config, &path, &base);
+        fs::remove_dir_all(&output_dir).unwrap();
+        findings
+    }
+
+    #[test]
+    fn overflow_panics_the_same_way_at_every_opt_level() {
+        let source_code = "fn main() {
+    let x: u8 = std::hint::black_box(255);
+    println!(\"{}\", x + 1);
+}";
+        assert!(check_source("overflow", source_code).is_empty());
+    }
+
+    #[test]
+    fn libraries_are_skipped() {
+        assert!(check_source("lib", "pub fn f() -> u8 { 1 }").is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::Path;
+
+use super::rustc::{self, Compiled};
+use super::{Finding, OracleConfig};
+use crate::runner::{self, Verdict};
+
+// rustc -Zunpretty=<mode> 로 mutant를 다시 출력하고, 그 출력을 다시 컴파일해본다.
+// 원본은 컴파일되는데 출력본이 안 되거나, 실행 결과가 달라지면 pretty printer 버그.
+const MODES: [&str; 2] = ["normal", "expanded"];
+const EXPANDED_NOISE: [&str; 1] = ["E0658"];
+
+fn unpretty(config: &OracleConfig, source_path: &Path, mode: &str) -> runner::RunResult {
+    let mut command = rustc::rustc_command(config);
+    command.arg(format!("-Zunpretty={}", mode)).arg(source_path);
+    runner::run_with_timeout(command, config.timeout)
+}
+
+pub fn check(config: &OracleConfig, mutant_path: &Path, base: &Compiled) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    for mode in MODES {
+        let printed = unpretty(config, mutant_path, mode);
+        match printed.verdict {
+            Verdict::Ice | Verdict::Crash => {
+                findings.push(Finding {
+                    oracle: "pretty",
+                    kind: "printer-ice",
+                    signature: format!("{} {}", mode, runner::ice_signature(&printed.stderr)),
+                    artifacts: vec![(format!("unpretty-{}.stderr", mode), printed.stderr)],
+                });
+                continue;
+            }
+            Verdict::Accepted => {}
+            // parse 조차 안 되는 mutant는 볼 필요 없다
+            Verdict::Rejected | Verdict::Timeout => continue,
+        }
+        // 원본이 컴파일되지 않으면 출력본과 비교할 의미가 없다
+        if base.run.verdict != Verdict::Accepted {
+            continue;
+        }
+
+        let printed_path = config.work_dir.join(format!("pretty_{}.rs", mode));
+        fs::write(&printed_path, &printed.stdout).unwrap();
+        let reprinted = rustc::compile(config, &printed_path, &format!("pretty_{}", mode), &[]);
+        let mut artifacts = vec![
+            (format!("unpretty-{}.rs", mode), printed.stdout.clone()),
+            (
+                format!("unpretty-{}.stderr", mode),
+                reprinted.run.stderr.clone(),
+            ),
+        ];
+
+        match reprinted.run.verdict {
+            Verdict::Ice | Verdict::Crash => findings.push(Finding {
+                oracle: "pretty",
+                kind: "reprint-ice",
+                signature: format!("{} {}", mode, runner::ice_signature(&reprinted.run.stderr)),
+                artifacts,
+            }),
+            Verdict::Rejected => {
+                let codes = runner::error_codes(&reprinted.run.stderr);
+                // expanded 출력은 format_args! 등의 내부 구현이 그대로 드러나서
+                // unstable feature 에러(E0658)가 나는 게 정상이다.
+                if mode == "expanded" && codes.iter().all(|c| EXPANDED_NOISE.contains(&c.as_str()))
+                {
+                    continue;
+                }
+                findings.push(Finding {
+                    oracle: "pretty",
+                    kind: "reprint-rejected",
+                    signature: format!(
+                        "{} {:?} {}",
+                        mode,
+                        codes,
+                        runner::first_error(&reprinted.run.stderr)
+                    ),
+                    artifacts,
+                })
+            }
+            Verdict::Timeout => {}
+            Verdict::Accepted => {
+                // 둘 다 실행파일이면 돌려서 출력과 종료 상태를 비교한다
+                if let (Some(original), Some(printed_binary)) = (&base.binary, &reprinted.binary) {
+                    let expected = runner::run_binary(original, config.timeout);
+                    let actual = runner::run_binary(printed_binary, config.timeout);
+                    if expected.verdict != Verdict::Timeout
+                        && (expected.verdict != actual.verdict || expected.stdout != actual.stdout)
+                    {
+                        artifacts.push(("original.stdout".to_string(), expected.stdout));
+                        artifacts.push((format!("unpretty-{}.stdout", mode), actual.stdout));
+                        findings.push(Finding {
+                            oracle: "pretty",
+                            kind: "meaning-changed",
+                            signature: format!(
+                                "{} {:?} -> {:?}",
+                                mode, expected.verdict, actual.verdict
+                            ),
+                            artifacts,
+                        });
+                        continue;
+                    }
+                }
+                // normal 출력은 다시 출력했을 때 그대로여야 한다
+                if mode == "normal" {
+                    let again = unpretty(config, &printed_path, mode);
+                    if again.verdict == Verdict::Accepted && again.stdout != printed.stdout {
+                        let signature = format!(
+                            "{} {}",
+                            mode,
+                            runner::first_diff_line(&printed.stdout, &again.stdout)
+                        );
+                        artifacts.push(("unpretty-normal-twice.rs".to_string(), again.stdout));
+                        findings.push(Finding {
+                            oracle: "pretty",
+                            kind: "unstable-print",
+                            signature,
+                            artifacts,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_of_a_plain_program_has_no_findings() {
+        let output_dir = std::env::temp_dir().join(format!("crate-pretty-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["pretty".to_string()], &output_dir);
+        let path = output_dir.join("mutant.rs");
+        fs::write(
+            &path,
+            "struct S(u8);\nfn main() {\n    let s = S(1);\n    println!(\"{}\", s.0 + 1);\n}\n",
+        )
+        .unwrap();
+        let base = rustc::compile(&config, &path, "base", &[]);
+        assert_eq!(base.run.verdict, Verdict::Accepted);
+        let findings = check(&config, &path, &base);
+        let kinds: Vec<&str> = findings.iter().map(|f| f.kind).collect();
+        assert!(kinds.is_empty(), "{:?}", kinds);
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{Finding, OracleConfig};
+use crate::runner::{self, RunResult, Verdict};
+
+pub struct Compiled {
+    pub run: RunResult,
+    // bin crate이고 컴파일에 성공했을 때만 Some
+    pub binary: Option<PathBuf>,
+}
+
+// fn main이 있으면 실행파일로, 없으면 lib으로 컴파일한다.
+pub fn is_executable(source_code: &str) -> bool {
+    source_code.contains("fn main(")
+}
+
+// rustc 호출의 기본 형태. 다른 oracle들도 이걸 가져다 인자를 덧붙인다.
+// -Z 옵션과 #![feature]를 stable rustc에서도 쓸 수 있도록 RUSTC_BOOTSTRAP을 켜둔다.
+// mut_input.rs_1.rs 같은 파일 이름은 crate 이름이 될 수 없어서 이름을 고정한다.
+pub fn rustc_command(config: &OracleConfig) -> Command {
+    let mut command = Command::new(&config.rustc);
+    command
+        .env("RUSTC_BOOTSTRAP", "1")
+        .arg("--edition=2021")
+        .arg("--crate-name=mutant")
+        .args(&config.rustc_args);
+    command
+}
+
+// tag는 work_dir 안에서 결과물 이름이 겹치지 않게 하는 용도.
+pub fn compile(
+    config: &OracleConfig,
+    source_path: &Path,
+    tag: &str,
+    extra_args: &[&str],
+) -> Compiled {
+    let source_code = fs::read_to_string(source_path).unwrap_or_default();
+    let executable = is_executable(&source_code);
+    let output = config.work_dir.join(format!("{}.out", tag));
+    let _ = fs::remove_file(&output);
+
+    let mut command = rustc_command(config);
+    command
+        .arg("--crate-type")
+        .arg(if executable { "bin" } else { "lib" })
+        .arg("-o")
+        .arg(&output)
+        .args(extra_args)
+        .arg(source_path);
+    let run = runner::run_with_timeout(command, config.timeout);
+
+    let binary = if executable && run.verdict == Verdict::Accepted {
+        Some(output)
+    } else {
+        None
+    };
+    Compiled { run, binary }
+}
+
+// 기본 oracle: 그냥 컴파일해서 ICE, crash, timeout을 잡는다.
+pub fn check(base: &Compiled) -> Vec<Finding> {
+    let kind = match base.run.verdict {
+        Verdict::Ice => "ice",
+        Verdict::Crash => "crash",
+        Verdict::Timeout => "timeout",
+        Verdict::Accepted | Verdict::Rejected => return vec![],
+    };
+    let signature = if base.run.verdict == Verdict::Timeout {
+        "timeout".to_string()
+    } else {
+        runner::ice_signature(&base.run.stderr)
+    };
+    vec![Finding {
+        oracle: "rustc",
+        kind,
+        signature,
+        artifacts: vec![("rustc.stderr".to_string(), base.run.stderr.clone())],
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compiled(verdict: Verdict, stderr: &str) -> Compiled {
+        Compiled {
+            run: RunResult {
+                verdict,
+                stdout: String::new(),
+                stderr: stderr.to_string(),
+            },
+            binary: None,
+        }
+    }
+
+    #[test]
+    fn only_ices_crashes_and_timeouts_are_findings() {
+        assert!(check(&compiled(Verdict::Accepted, "")).is_empty());
+        assert!(check(&compiled(
+            Verdict::Rejected,
+            "error[E0308]: mismatched types"
+        ))
+        .is_empty());
+        let ice = check(&compiled(
+            Verdict::Ice,
+            "error: internal compiler error: broken MIR in 3",
+        ));
+        assert_eq!(ice.len(), 1);
+        assert_eq!(
+            (ice[0].kind, ice[0].signature.as_str()),
+            ("ice", "broken MIR in")
+        );
+        let timeout = check(&compiled(Verdict::Timeout, ""));
+        assert_eq!(
+            (timeout[0].kind, timeout[0].signature.as_str()),
+            ("timeout", "timeout")
+        );
+    }
+
+    #[test]
+    fn compile_builds_binaries_only_for_main() {
+        let output_dir = std::env::temp_dir().join(format!("crate-rustc-{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustc".to_string()], &output_dir);
+        let bin = output_dir.join("bin.rs");
+        let lib = output_dir.join("lib.rs");
+        fs::write(&bin, "fn main() {}").unwrap();
+        fs::write(&lib, "pub fn f() {}").unwrap();
+        let compiled = compile(&config, &bin, "bin", &[]);
+        assert_eq!(compiled.run.verdict, Verdict::Accepted);
+        assert!(compiled.binary.unwrap().exists());
+        let compiled = compile(&config, &lib, "lib", &[]);
+        assert_eq!(compiled.run.verdict, Verdict::Accepted);
+        assert!(compiled.binary.is_none());
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+}
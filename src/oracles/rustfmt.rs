@@ -0,0 +1,204 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::rustc::{self, Compiled};
+use super::{Finding, OracleConfig};
+use crate::runner::{self, RunResult, Verdict};
+
+// rustfmt로 파일을 제자리에서 포맷한다.
+// import와 mod 정렬은 rustfmt가 원래 하는 일이라 token 비교에 걸리지 않게 끈다.
+fn format_in_place(config: &OracleConfig, path: &Path) -> RunResult {
+    let mut command = Command::new(&config.rustfmt);
+    command
+        .arg("--edition=2021")
+        .arg("--config")
+        .arg("reorder_imports=false,reorder_modules=false")
+        .arg(path);
+    runner::run_with_timeout(command, config.timeout)
+}
+
+// `use a::{b}` 의 중괄호. rustfmt가 `use a::b` 로 바꾸는 건 정상이다
+fn single_use_brace(node: &tree_sitter::Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let mut cursor = parent.walk();
+    let elements = parent
+        .named_children(&mut cursor)
+        .filter(|c| !c.kind().ends_with("comment"))
+        .count();
+    parent.kind() == "use_list" && elements == 1 && (node.kind() == "{" || node.kind() == "}")
+}
+
+// tree-sitter의 leaf node를 token처럼 쓴다.
+// 주석은 token이 아니고, 쉼표는 rustfmt가 원래 붙였다 뗐다 하므로 비교에서 뺀다.
+pub fn tokens(source_code: &str) -> Vec<String> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_rust::language()).unwrap();
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut acc = vec![];
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        if node.child_count() == 0 || node.kind().ends_with("comment") {
+            let kind = node.kind();
+            if !kind.ends_with("comment") && kind != "," && !single_use_brace(&node) {
+                acc.push(source_code[node.start_byte()..node.end_byte()].to_string());
+            }
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return acc;
+            }
+        }
+    }
+}
+
+// rustc가 받아준 mutant만 rustfmt에 넣는다.
+// panic, 두 번 포맷했을 때 결과가 다른 것, token이 바뀐 것, 포맷 후 컴파일이 안 되는 것을 잡는다.
+pub fn check(config: &OracleConfig, mutant_path: &Path, base: &Compiled) -> Vec<Finding> {
+    if base.run.verdict != Verdict::Accepted {
+        return vec![];
+    }
+    let original = fs::read_to_string(mutant_path).unwrap_or_default();
+    let once_path = config.work_dir.join("rustfmt_once.rs");
+    let twice_path = config.work_dir.join("rustfmt_twice.rs");
+
+    fs::write(&once_path, &original).unwrap();
+    let first = format_in_place(config, &once_path);
+    let once = fs::read_to_string(&once_path).unwrap_or_default();
+    match first.verdict {
+        Verdict::Ice | Verdict::Crash => {
+            return vec![Finding {
+                oracle: "rustfmt",
+                kind: "panic",
+                signature: runner::ice_signature(&first.stderr),
+                artifacts: vec![("rustfmt.stderr".to_string(), first.stderr)],
+            }]
+        }
+        Verdict::Timeout => {
+            return vec![Finding {
+                oracle: "rustfmt",
+                kind: "timeout",
+                signature: "timeout".to_string(),
+                artifacts: vec![],
+            }]
+        }
+        // 컴파일되는 코드에 대해 rustfmt 스스로 internal error를 내는 경우
+        Verdict::Rejected => {
+            if first.stderr.contains("error[internal]") {
+                return vec![Finding {
+                    oracle: "rustfmt",
+                    kind: "internal-error",
+                    signature: runner::first_error(&first.stderr),
+                    artifacts: vec![("rustfmt.stderr".to_string(), first.stderr)],
+                }];
+            }
+            return vec![];
+        }
+        Verdict::Accepted => {}
+    }
+
+    let mut findings = vec![];
+    let mut artifacts = vec![("rustfmt_once.rs".to_string(), once.clone())];
+
+    fs::write(&twice_path, &once).unwrap();
+    let second = format_in_place(config, &twice_path);
+    let twice = fs::read_to_string(&twice_path).unwrap_or_default();
+    if second.verdict == Verdict::Accepted && twice != once {
+        let mut artifacts = artifacts.clone();
+        artifacts.push(("rustfmt_twice.rs".to_string(), twice.clone()));
+        findings.push(Finding {
+            oracle: "rustfmt",
+            kind: "not-idempotent",
+            signature: runner::first_diff_line(&once, &twice),
+            artifacts,
+        });
+    }
+
+    let before = tokens(&original);
+    let after = tokens(&once);
+    if before != after {
+        let (a, b) = before
+            .iter()
+            .zip(after.iter())
+            .find(|(a, b)| a != b)
+            .map(|(a, b)| (a.as_str(), b.as_str()))
+            .unwrap_or(("<eof>", "<eof>"));
+        findings.push(Finding {
+            oracle: "rustfmt",
+            kind: "token-changed",
+            signature: format!("{} -> {}", a, b),
+            artifacts: artifacts.clone(),
+        });
+    }
+
+    let formatted = rustc::compile(config, &once_path, "rustfmt", &[]);
+    match formatted.run.verdict {
+        Verdict::Accepted | Verdict::Timeout => {}
+        _ => {
+            artifacts.push(("rustc.stderr".to_string(), formatted.run.stderr.clone()));
+            findings.push(Finding {
+                oracle: "rustfmt",
+                kind: "breaks-compile",
+                signature: format!(
+                    "{:?} {:?} {}",
+                    formatted.run.verdict,
+                    runner::error_codes(&formatted.run.stderr),
+                    runner::first_error(&formatted.run.stderr)
+                ),
+                artifacts,
+            });
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::RunResult;
+
+    #[test]
+    fn tokens_skip_comments_and_commas() {
+        assert_eq!(
+            tokens("fn f(a: u8, /* x */ b: u8) {}"),
+            ["fn", "f", "(", "a", ":", "u8", "b", ":", "u8", ")", "{", "}"]
+        );
+    }
+
+    #[test]
+    fn tokens_ignore_single_use_braces() {
+        assert_eq!(tokens("use std::{io};"), tokens("use std::io;"));
+        assert_eq!(tokens("use std::{io as i};"), tokens("use std::io as i;"));
+        assert_ne!(tokens("use std::{io, fmt};"), tokens("use std::io, fmt;"));
+    }
+
+    #[test]
+    fn reordered_imports_are_not_token_changes() {
+        let output_dir =
+            std::env::temp_dir().join(format!("rustfmt_oracle_{}", std::process::id()));
+        let config = OracleConfig::new(vec!["rustfmt".to_string()], &output_dir);
+        let mutant_path = output_dir.join("mutant.rs");
+        fs::write(
+            &mutant_path,
+            "use std::{io, fmt};\nuse std::collections::{HashMap};\nmod b {}\nmod a {}\n",
+        )
+        .unwrap();
+        let base = Compiled {
+            run: RunResult {
+                verdict: Verdict::Accepted,
+                stdout: String::new(),
+                stderr: String::new(),
+            },
+            binary: None,
+        };
+        let findings = check(&config, &mutant_path, &base);
+        let _ = fs::remove_dir_all(&output_dir);
+        assert!(findings.iter().all(|f| f.kind != "token-changed"));
+    }
+}
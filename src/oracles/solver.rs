@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use super::rustc::{self, Compiled};
+use super::{Finding, OracleConfig};
+use crate::runner::{self, Verdict};
+
+// 기본 trait solver와 -Znext-solver (옵션으로 -Zpolonius) 결과를 비교하는 differential oracle.
+
+// trait system과 관련된 node 종류. 이게 많은 seed일수록 solver 차이가 드러나기 쉬워서
+// --rounds 의 seed queue가 이 수로 energy를 늘리거나 줄인다.
+const TRAIT_KINDS: [&str; 11] = [
+    "trait_item",
+    "impl_item",
+    "where_clause",
+    "where_predicate",
+    "trait_bounds",
+    "higher_ranked_trait_bound",
+    "associated_type",
+    "type_parameters",
+    "abstract_type",
+    "dynamic_type",
+    "bounded_type",
+];
+
+// node walk로 모은 node kind 중 trait 관련 node가 몇 개인지 센다.
+pub fn trait_weight(source_code: &str) -> usize {
+    crate::get_splice_parts(&source_code.to_string())
+        .iter()
+        .filter(|(kind, ..)| TRAIT_KINDS.contains(kind))
+        .count()
+}
+
+fn compare(name: &str, base: &Compiled, other: &Compiled) -> Option<(&'static str, String)> {
+    let (a, b) = (base.run.verdict, other.run.verdict);
+    if a == Verdict::Timeout || b == Verdict::Timeout {
+        return None;
+    }
+    let a_codes = runner::error_codes(&base.run.stderr);
+    let b_codes = runner::error_codes(&other.run.stderr);
+    match (a == Verdict::Ice, b == Verdict::Ice) {
+        // 양쪽 다 ICE면 solver 차이가 아니라 그냥 ICE다 (rustc oracle이 잡는다)
+        (true, true) => None,
+        (true, false) => Some((
+            "ice-default-only",
+            format!("{} {}", name, runner::ice_signature(&base.run.stderr)),
+        )),
+        (false, true) => Some((
+            "ice-other-only",
+            format!("{} {}", name, runner::ice_signature(&other.run.stderr)),
+        )),
+        (false, false) if a != b => Some((
+            "accept-reject",
+            format!("{} {:?} {:?} -> {:?} {:?}", name, a, a_codes, b, b_codes),
+        )),
+        (false, false) if a_codes != b_codes => Some((
+            "error-codes",
+            format!("{} {:?} -> {:?}", name, a_codes, b_codes),
+        )),
+        _ => None,
+    }
+}
+
+pub fn check(config: &OracleConfig, mutant_path: &Path, base: &Compiled) -> Vec<Finding> {
+    let mut variants = vec![("next-solver", "-Znext-solver")];
+    if config.solver_polonius {
+        variants.push(("polonius", "-Zpolonius"));
+    }
+
+    let mut findings = vec![];
+    for (name, flag) in variants {
+        let other = rustc::compile(config, mutant_path, name, &[flag]);
+        if let Some((kind, signature)) = compare(name, base, &other) {
+            findings.push(Finding {
+                oracle: "solver",
+                kind,
+                signature,
+                artifacts: vec![
+                    ("default.stderr".to_string(), base.run.stderr.clone()),
+                    (format!("{}.stderr", name), other.run.stderr),
+                ],
+            });
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::RunResult;
+
+    fn compiled(verdict: Verdict, stderr: &str) -> Compiled {
+        Compiled {
+            run: RunResult {
+                verdict,
+                stdout: String::new(),
+                stderr: stderr.to_string(),
+            },
+            binary: None,
+        }
+    }
+
+    const E0277: &str = "error[E0277]: the trait bound `u8: A` is not satisfied";
+    const E0308: &str = "error[E0308]: mismatched types";
+    const ICE: &str = "error: internal compiler error: no type for node 12";
+
+    #[test]
+    fn compare_agreeing_solvers() {
+        let accepted = compiled(Verdict::Accepted, "");
+        let rejected = compiled(Verdict::Rejected, E0277);
+        assert_eq!(compare("next-solver", &accepted, &accepted), None);
+        assert_eq!(compare("next-solver", &rejected, &rejected), None);
+        let ice = compiled(Verdict::Ice, ICE);
+        assert_eq!(compare("next-solver", &ice, &ice), None);
+        let timeout = compiled(Verdict::Timeout, "");
+        assert_eq!(compare("next-solver", &accepted, &timeout), None);
+    }
+
+    #[test]
+    fn compare_reports_disagreements() {
+        let accepted = compiled(Verdict::Accepted, "");
+        let rejected = compiled(Verdict::Rejected, E0277);
+        assert_eq!(
+            compare("next-solver", &accepted, &rejected),
+            Some((
+                "accept-reject",
+                "next-solver Accepted [] -> Rejected [\"E0277\"]".to_string()
+            ))
+        );
+        assert_eq!(
+            compare("polonius", &rejected, &compiled(Verdict::Rejected, E0308)),
+            Some((
+                "error-codes",
+                "polonius [\"E0277\"] -> [\"E0308\"]".to_string()
+            ))
+        );
+        let ice = compiled(Verdict::Ice, ICE);
+        assert_eq!(
+            compare("next-solver", &accepted, &ice),
+            Some(("ice-other-only", "next-solver no type for node".to_string()))
+        );
+        assert_eq!(
+            compare("next-solver", &ice, &rejected).map(|(kind, _)| kind),
+            Some("ice-default-only")
+        );
+    }
+
+    #[test]
+    fn trait_weight_counts_trait_nodes() {
+        assert_eq!(trait_weight("fn main() { let x = 1 + 2; }"), 0);
+        assert!(trait_weight("trait A {} impl A for u8 {} fn f<T: A>() where T: Copy {}") >= 4);
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 외부 프로세스(rustc, rustfmt, ...)를 돌린 결과를 분류한 것.
+// 각 oracle은 이 verdict를 기준으로 finding을 만든다.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    // exit code 0
+    Accepted,
+    // 일반적인 에러로 종료 (컴파일 에러 등)
+    Rejected,
+    // internal compiler error, panic
+    Ice,
+    // signal로 죽은 경우 (stack overflow 등)
+    Crash,
+    Timeout,
+}
+
+pub struct RunResult {
+    pub verdict: Verdict,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut p) = pipe {
+            let _ = p.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+// command를 실행하고 timeout(초)이 지나면 kill한다.
+// stdout/stderr는 pipe가 가득 차서 멈추지 않도록 thread로 따로 읽는다.
+pub fn run_with_timeout(mut command: Command, timeout: u64) -> RunResult {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // --rustc, --rustfmt, --miri 경로가 틀리면 모든 mutant가 crash로 잡히니 바로 멈춘다
+    let mut child = command
+        .spawn()
+        .unwrap_or_else(|e| panic!("Cannot run {:?}: {}", command.get_program(), e));
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {
+                if start.elapsed() > Duration::from_secs(timeout) {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break None;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(_) => break None,
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    let verdict = match status {
+        None => Verdict::Timeout,
+        Some(status) => {
+            if is_ice(&stderr) || status.code() == Some(101) {
+                Verdict::Ice
+            } else if status.success() {
+                Verdict::Accepted
+            } else if status.code().is_none() {
+                // unix에서 signal로 죽으면 exit code가 없다
+                Verdict::Crash
+            } else {
+                Verdict::Rejected
+            }
+        }
+    };
+    RunResult {
+        verdict,
+        stdout,
+        stderr,
+    }
+}
+
+// 빌드된 실행파일을 돌린다. 컴파일러가 아닌 프로그램이므로 panic(101)은 ICE가 아니다.
+pub fn run_binary(binary: &Path, timeout: u64) -> RunResult {
+    let mut result = run_with_timeout(Command::new(binary), timeout);
+    if result.verdict == Verdict::Ice {
+        result.verdict = Verdict::Rejected;
+    }
+    result
+}
+
+pub fn is_ice(stderr: &str) -> bool {
+    stderr.contains("error: internal compiler error")
+        || stderr.contains("thread 'rustc' panicked")
+        || stderr.contains("has overflowed its stack")
+}
+
+// 같은 버그를 한 bucket으로 모으기 위한 signature.
+// panic 위치와 메시지 첫 줄을 쓰고, 숫자(DefId, 줄 번호 등)는 지워서 mutant마다 달라지지 않게 한다.
+pub fn ice_signature(stderr: &str) -> String {
+    let lines: Vec<&str> = stderr.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(pos) = line.find("panicked at ") {
+            let location = line[pos + "panicked at ".len()..].trim_end_matches(':');
+            let message = lines.get(i + 1).copied().unwrap_or("");
+            return format!("{} {}", location, normalize(message));
+        }
+        if let Some(pos) = line.find("internal compiler error: ") {
+            return normalize(&line[pos + "internal compiler error: ".len()..]);
+        }
+        if line.contains("has overflowed its stack") {
+            return "stack overflow".to_string();
+        }
+    }
+    normalize(lines.iter().find(|l| l.starts_with("error")).unwrap_or(&""))
+}
+
+// error[E0308] 같은 error code를 모은다.
+pub fn error_codes(stderr: &str) -> Vec<String> {
+    let mut codes: Vec<String> = stderr
+        .lines()
+        .filter_map(|l| l.strip_prefix("error[")?.split(']').next())
+        .map(|c| c.to_string())
+        .collect();
+    codes.sort();
+    codes.dedup();
+    codes
+}
+
+pub fn first_error(stderr: &str) -> String {
+    normalize(
+        stderr
+            .lines()
+            .find(|l| l.starts_with("error"))
+            .unwrap_or(""),
+    )
+}
+
+// 두 출력이 처음으로 달라지는 줄. 숫자는 지워서 bucket이 너무 잘게 나뉘지 않게 한다.
+// 한쪽이 다른 쪽의 앞부분이면 더 긴 쪽에만 있는 첫 줄을 쓴다.
+pub fn first_diff_line(a: &str, b: &str) -> String {
+    let mut a_lines = a.lines();
+    let mut b_lines = b.lines();
+    loop {
+        match (a_lines.next(), b_lines.next()) {
+            (Some(x), Some(y)) if x == y => continue,
+            (Some(x), _) => return normalize(x),
+            (None, Some(y)) => return normalize(y),
+            (None, None) => return String::new(),
+        }
+    }
+}
+
+fn normalize(message: &str) -> String {
+    let message: String = message
+        .chars()
+        .filter(|c| !c.is_ascii_digit())
+        .take(120)
+        .collect();
+    message.trim().to_string()
+}
+
+pub fn bucket_id(signature: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    signature.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICE: &str = "error: internal compiler error: compiler/rustc_middle/src/ty/mod.rs:1234:5: unexpected DefId(0:12)
+
+thread 'rustc' panicked at compiler/rustc_middle/src/ty/mod.rs:1234:5:
+Box<dyn Any>
+note: run with `RUST_BACKTRACE=1`";
+
+    #[test]
+    fn ice_signature_drops_numbers() {
+        assert_eq!(
+            ice_signature(ICE),
+            "compiler/rustc_middle/src/ty/mod.rs::: unexpected DefId(:)"
+        );
+        assert_eq!(
+            ice_signature("thread 'rustc' panicked at src/lib.rs:10:3:\nindex out of bounds: 5\n"),
+            "src/lib.rs:10:3 index out of bounds:"
+        );
+        assert_eq!(
+            ice_signature("\nthread 'rustc' has overflowed its stack\n"),
+            "stack overflow"
+        );
+        assert_eq!(
+            ice_signature("warning: unused\nerror: aborting due to 2 previous errors\n"),
+            "error: aborting due to  previous errors"
+        );
+        assert!(is_ice(ICE));
+        assert!(!is_ice("error[E0308]: mismatched types"));
+    }
+
+    #[test]
+    fn error_codes_are_sorted_and_unique() {
+        let stderr = "error[E0308]: mismatched types
+ --> a.rs:1:1
+error[E0277]: the trait bound is not satisfied
+error[E0308]: mismatched types
+error: aborting due to 3 previous errors
+warning[E0170]: not an error";
+        assert_eq!(error_codes(stderr), ["E0277", "E0308"]);
+        assert!(error_codes("warning: unused variable").is_empty());
+        assert_eq!(first_error(stderr), "error[E]: mismatched types");
+    }
+
+    #[test]
+    fn first_diff_line_finds_the_changed_line() {
+        assert_eq!(first_diff_line("a\nb 1\nc", "a\nb 2\nc"), "b");
+        assert_eq!(first_diff_line("a\nb", "a\nb"), "");
+    }
+
+    #[test]
+    fn first_diff_line_handles_prefixes() {
+        assert_eq!(first_diff_line("a\nb", "a\nb\nextra line"), "extra line");
+        assert_eq!(first_diff_line("a\nb\nextra line", "a\nb"), "extra line");
+        assert_eq!(first_diff_line("", "fn main() {}"), "fn main() {}");
+    }
+
+    #[test]
+    fn bucket_id_is_stable() {
+        assert_eq!(bucket_id("sig"), bucket_id("sig"));
+        assert_ne!(bucket_id("sig"), bucket_id("other"));
+        assert_eq!(bucket_id("sig").len(), 16);
+    }
+
+    fn sh(script: &str, timeout: u64) -> RunResult {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        run_with_timeout(command, timeout)
+    }
+
+    #[test]
+    fn run_with_timeout_classifies_exits() {
+        let result = sh("echo out; echo err >&2", 5);
+        assert_eq!(result.verdict, Verdict::Accepted);
+        assert_eq!(result.stdout, "out\n");
+        assert_eq!(result.stderr, "err\n");
+        assert_eq!(sh("exit 1", 5).verdict, Verdict::Rejected);
+        assert_eq!(sh("exit 101", 5).verdict, Verdict::Ice);
+        assert_eq!(sh("kill -SEGV $$", 5).verdict, Verdict::Crash);
+        assert_eq!(sh("exec sleep 5", 1).verdict, Verdict::Timeout);
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::fs;
+
+use rand::seq::SliceRandom;
+
+use crate::TypePosInfo;
+
+// mutant 하나를 만들 때 어떤 mode(operator)와 어떤 node 종류를 바꿀지 고르는 scheduler.
+// mutator들은 받은 node 중 하나를 균등하게 고르기 때문에 `;`, `,` 같은 token이 item만큼 자주 뽑힌다.
+// 그래서 여기서 mode와 kind를 weight대로 먼저 고르고, 그 kind의 node만 mutator에 넘겨 mutant를 하나 만든다.
+// weight는 --schedule 파일에서 읽고, --adaptive 를 켜면 새 bucket이나 처음 보는 진단을 만든
+// mode와 kind 쪽으로 weight를 옮긴다. (UCB1 점수에 비례해서 뽑는 multi-armed bandit)
+
+// 글자가 하나도 없는 kind (`;`, `,`, `::`, `->` 같은 token) 의 기본 weight
+const TOKEN_WEIGHT: f64 = 0.1;
+
+// 고른 mode와 kind로 mutant가 안 나오면 다시 고르는 횟수
+const RETRIES: usize = 64;
+
+// 이 mode들만 node 목록을 받아서 mutant를 만든다
+pub const SCHEDULED_MODES: [i32; 16] = [0, 1, 6, 7, 8, 9, 10, 11, 12, 14, 15, 16, 17, 18, 19, 20];
+
+// modes 중 scheduler가 고를 수 없는 첫 mode
+pub fn unschedulable(modes: &[i32]) -> Option<i32> {
+    modes
+        .iter()
+        .copied()
+        .find(|mode| !SCHEDULED_MODES.contains(mode))
+}
+
+#[derive(Default, Clone, Copy)]
+struct Arm {
+    // 이 arm으로 만든 mutant 수
+    pulls: usize,
+    // 그 중 새 bucket이나 처음 보는 진단을 만든 mutant 수
+    wins: usize,
+}
+
+pub struct Schedule {
+    modes: Vec<i32>,
+    mode_weights: HashMap<i32, f64>,
+    kind_weights: HashMap<String, f64>,
+    adaptive: bool,
+    mode_arms: HashMap<i32, Arm>,
+    kind_arms: HashMap<&'static str, Arm>,
+    pulls: usize,
+}
+
+impl Schedule {
+    // weight 파일은 한 줄에 "kind <node kind> <weight>" 또는 "mode <mode> <weight>".
+    // # 로 시작하는 줄은 무시하고, 파일에 없는 mode와 kind는 기본 weight를 쓴다.
+    pub fn new(modes: Vec<i32>, weights: Option<&str>, adaptive: bool) -> Schedule {
+        if let Some(mode) = unschedulable(&modes) {
+            panic!("Mode {} cannot be scheduled.", mode);
+        }
+        let mut schedule = Schedule {
+            modes,
+            mode_weights: HashMap::new(),
+            kind_weights: HashMap::new(),
+            adaptive,
+            mode_arms: HashMap::new(),
+            kind_arms: HashMap::new(),
+            pulls: 0,
+        };
+        if let Some(weights) = weights {
+            let text = fs::read_to_string(weights).expect("Cannot read schedule weights.");
+            for line in text.lines().map(str::trim) {
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let weight = parts.get(2).and_then(|w| w.parse::<f64>().ok());
+                match (parts.as_slice(), weight) {
+                    (["kind", kind, _], Some(weight)) if weight >= 0.0 => {
+                        schedule.kind_weights.insert(kind.to_string(), weight);
+                    }
+                    (["mode", mode, _], Some(weight)) if weight >= 0.0 => {
+                        let mode = mode
+                            .parse::<i32>()
+                            .unwrap_or_else(|_| panic!("Invalid schedule line: {}", line));
+                        schedule.mode_weights.insert(mode, weight);
+                    }
+                    _ => panic!("Invalid schedule line: {}", line),
+                }
+            }
+        }
+        schedule
+    }
+
+    fn kind_weight(&self, kind: &str) -> f64 {
+        match self.kind_weights.get(kind) {
+            Some(weight) => *weight,
+            None if kind.chars().any(|c| c.is_alphabetic()) => 1.0,
+            None => TOKEN_WEIGHT,
+        }
+    }
+
+    // adaptive가 아니면 1. 맞으면 UCB1 점수: 평균 보상 + 덜 뽑힌 arm을 위한 탐색 항
+    fn score(&self, arm: Option<&Arm>) -> f64 {
+        if !self.adaptive {
+            return 1.0;
+        }
+        let arm = arm.copied().unwrap_or_default();
+        let mean = (arm.wins as f64 + 1.0) / (arm.pulls as f64 + 2.0);
+        let exploration = (2.0 * ((self.pulls + 1) as f64).ln() / (arm.pulls + 1) as f64).sqrt();
+        mean + exploration
+    }
+
+    fn pick_mode(&self) -> Option<i32> {
+        let mut rng = rand::thread_rng();
+        self.modes
+            .choose_weighted(&mut rng, |mode| {
+                self.mode_weights.get(mode).copied().unwrap_or(1.0)
+                    * self.score(self.mode_arms.get(mode))
+            })
+            .ok()
+            .copied()
+    }
+
+    fn pick_kind(&self, nodes: &[TypePosInfo<'static>]) -> Option<&'static str> {
+        let mut kinds: Vec<&'static str> = nodes.iter().map(|(kind, ..)| *kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        kinds
+            .choose_weighted(&mut rand::thread_rng(), |kind| {
+                self.kind_weight(kind) * self.score(self.kind_arms.get(kind))
+            })
+            .ok()
+            .copied()
+    }
+
+    // mode와 kind를 고르고, 그 kind의 node만 mutate에 넘겨 mutant 하나를 만든다.
+    // mutate는 (mode, node 목록) 을 받아 mutant를 하나(또는 0개) 돌려줘야 한다.
+    pub fn next<F>(
+        &self,
+        nodes: &[TypePosInfo<'static>],
+        mut mutate: F,
+    ) -> Option<(i32, &'static str, String)>
+    where
+        F: FnMut(i32, &Vec<TypePosInfo<'static>>) -> Vec<String>,
+    {
+        for _ in 0..RETRIES {
+            let mode = self.pick_mode()?;
+            let kind = self.pick_kind(nodes)?;
+            let picked: Vec<TypePosInfo<'static>> =
+                nodes.iter().filter(|(k, ..)| *k == kind).copied().collect();
+            if let Some(mutant) = mutate(mode, &picked).pop() {
+                return Some((mode, kind, mutant));
+            }
+        }
+        None
+    }
+
+    // next로 만든 mutant의 oracle 결과를 알려준다
+    pub fn reward(&mut self, mode: i32, kind: &'static str, interesting: bool) {
+        self.pulls += 1;
+        for arm in [
+            self.mode_arms.entry(mode).or_default(),
+            self.kind_arms.entry(kind).or_default(),
+        ] {
+            arm.pulls += 1;
+            if interesting {
+                arm.wins += 1;
+            }
+        }
+    }
+
+    pub fn print_summary(&self) {
+        println!("Schedule (mode / kind : interesting / mutants):");
+        let mut modes: Vec<(&i32, &Arm)> = self.mode_arms.iter().collect();
+        modes.sort_by_key(|(mode, _)| **mode);
+        for (mode, arm) in modes {
+            println!("  mode {} : {} / {}", mode, arm.wins, arm.pulls);
+        }
+        let mut kinds: Vec<(&&str, &Arm)> = self.kind_arms.iter().collect();
+        kinds.sort_by_key(|(kind, arm)| (std::cmp::Reverse((arm.wins, arm.pulls)), **kind));
+        for (kind, arm) in kinds.into_iter().take(20) {
+            println!("  {} : {} / {}", kind, arm.wins, arm.pulls);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Point;
+
+    fn weights_file(name: &str, text: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}_{}.txt", name, std::process::id()));
+        fs::write(&path, text).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn node(kind: &'static str) -> TypePosInfo<'static> {
+        (kind, 0, 1, Point::new(0, 0), Point::new(0, 1))
+    }
+
+    #[test]
+    fn splice_and_generator_modes_are_unschedulable() {
+        assert_eq!(unschedulable(&[6, 7, 14]), None);
+        assert_eq!(unschedulable(&[2, 7]), Some(2));
+        assert_eq!(unschedulable(&[7, 22]), Some(22));
+    }
+
+    #[test]
+    fn weights_are_parsed() {
+        let path = weights_file(
+            "weights",
+            "# comment\n\nkind ; 0.5\nkind function_item 3\nmode 12 0\n",
+        );
+        let schedule = Schedule::new(vec![7, 12], Some(&path), false);
+        let _ = fs::remove_file(&path);
+        assert_eq!(schedule.kind_weight(";"), 0.5);
+        assert_eq!(schedule.kind_weight("function_item"), 3.0);
+        assert_eq!(schedule.kind_weight("identifier"), 1.0);
+        assert_eq!(schedule.kind_weight(","), TOKEN_WEIGHT);
+        // weight 0인 mode는 뽑히지 않는다
+        for _ in 0..100 {
+            assert_eq!(schedule.pick_mode(), Some(7));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid schedule line: kind ; -1")]
+    fn negative_weight_is_rejected() {
+        let path = weights_file("negative", "kind ; -1\n");
+        Schedule::new(vec![7], Some(&path), false);
+    }
+
+    #[test]
+    fn next_passes_only_nodes_of_the_picked_kind() {
+        let schedule = Schedule::new(vec![7], None, false);
+        let nodes = vec![node("identifier"), node("block"), node("identifier")];
+        for _ in 0..20 {
+            let (mode, kind, _) = schedule
+                .next(&nodes, |_, picked| {
+                    assert!(picked.iter().all(|(k, ..)| *k == picked[0].0));
+                    vec![picked[0].0.to_string()]
+                })
+                .unwrap();
+            assert_eq!(mode, 7);
+            assert!(kind == "identifier" || kind == "block");
+        }
+        assert!(schedule.next(&nodes, |_, _| vec![]).is_none());
+    }
+
+    #[test]
+    fn adaptive_score_prefers_winning_arms() {
+        let mut schedule = Schedule::new(vec![7, 8], None, true);
+        for _ in 0..50 {
+            schedule.reward(7, "block", true);
+            schedule.reward(8, "identifier", false);
+        }
+        assert!(
+            schedule.score(schedule.mode_arms.get(&7)) > schedule.score(schedule.mode_arms.get(&8))
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::oracles::{solver, Feedback};
+
+// --rounds 로 켜는 seed queue. 모든 seed에 같은 수의 mutant를 주고 끝내는 대신
+// seed마다 성과(새 bucket, 처음 본 진단과 (진단, node 종류) 쌍, 컴파일 성공 비율)를 기록하고,
+// AFL의 power schedule처럼 성과가 좋은 seed에 energy(이번 round에 만들 mutant 수)를 더 준다.
+// 새 bucket이나 처음 보는 진단을 만든 mutant는 다음 round부터 seed로 같이 변이한다.
+// 이미 있던 seed와 내용이 같은 mutant는 넣지 않고, queue가 --max-queue 보다 커지면
+// round가 끝날 때 energy가 가장 낮은 추가 seed부터 버린다. 입력 파일은 버리지 않는다.
+
+// 새 bucket 하나, 처음 본 진단 하나를 처음 본 (진단, node 종류) 쌍 몇 개만큼 쳐줄지
+const BUCKET_SCORE: usize = 8;
+const DIAGNOSTIC_SCORE: usize = 2;
+
+// --file-count 에 곱하는 energy 배율의 범위
+const MIN_FACTOR: f64 = 0.25;
+const MAX_FACTOR: f64 = 16.0;
+
+// solver oracle을 쓸 때 trait 관련 node가 이만큼 있는 seed는 energy 배율이 1이다.
+// 하나도 없으면 0.5배, 많으면 최대 2배
+const TRAIT_NODES: f64 = 8.0;
+const MAX_TRAIT_FACTOR: f64 = 2.0;
+
+pub struct Seed {
+    pub name: String,
+    pub source_code: String,
+    // 입력 파일이면 None, 추가된 mutant면 그 mutant를 만든 seed 이름
+    parent: Option<String>,
+    executions: usize,
+    accepted: usize,
+    new_buckets: usize,
+    new_diagnostics: usize,
+    new_pairs: usize,
+    // 이 seed의 mutant 중 queue에 추가된 수
+    children: usize,
+    // solver::trait_weight
+    trait_nodes: usize,
+}
+
+pub struct SeedQueue {
+    pub seeds: Vec<Seed>,
+    // --file-count. 아직 돌려보지 않은 seed의 energy
+    base_energy: usize,
+    // solver oracle이 켜져 있으면 trait 관련 node가 적은 seed의 energy를 줄인다
+    trait_weighted: bool,
+    // 추가된 seed를 저장하는 곳. 다음 campaign의 --input-dir 로 쓸 수 있다
+    queue_dir: PathBuf,
+    // --max-queue
+    max_seeds: usize,
+    // 지금까지 queue에 들어왔던 모든 seed 내용의 hash. 버린 seed가 다시 들어오지 않게 남겨둔다
+    hashes: HashSet<u64>,
+    // 추가된 seed 이름에 붙이는 번호
+    next_id: usize,
+    evicted: usize,
+}
+
+fn content_hash(source_code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source_code.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Seed {
+    fn new(name: String, source_code: String, parent: Option<String>) -> Seed {
+        Seed {
+            trait_nodes: solver::trait_weight(&source_code),
+            name,
+            source_code,
+            parent,
+            executions: 0,
+            accepted: 0,
+            new_buckets: 0,
+            new_diagnostics: 0,
+            new_pairs: 0,
+            children: 0,
+        }
+    }
+
+    fn score(&self) -> usize {
+        self.new_buckets * BUCKET_SCORE + self.new_diagnostics * DIAGNOSTIC_SCORE + self.new_pairs
+    }
+}
+
+impl SeedQueue {
+    // 다른 mode들처럼 500줄 이상인 파일은 쓰지 않는다
+    pub fn new(
+        input_dir: &str,
+        base_energy: usize,
+        trait_weighted: bool,
+        max_seeds: usize,
+        output_dir: &Path,
+    ) -> SeedQueue {
+        let mut seeds = vec![];
+        for entry in WalkDir::new(input_dir).into_iter().flatten() {
+            let path = entry.path();
+            if !path.is_file() || path.extension().map(|e| e != "rs").unwrap_or(true) {
+                continue;
+            }
+            let Ok(source_code) = fs::read_to_string(path) else {
+                continue;
+            };
+            if source_code.lines().count() < 500 {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                seeds.push(Seed::new(name, source_code, None));
+            }
+        }
+        let queue_dir = output_dir.join("queue");
+        fs::create_dir_all(&queue_dir).unwrap();
+        let hashes = seeds.iter().map(|s| content_hash(&s.source_code)).collect();
+        SeedQueue {
+            next_id: seeds.len(),
+            seeds,
+            base_energy,
+            trait_weighted,
+            queue_dir,
+            max_seeds,
+            hashes,
+            evicted: 0,
+        }
+    }
+
+    fn trait_factor(&self, seed: &Seed) -> f64 {
+        if !self.trait_weighted {
+            return 1.0;
+        }
+        (0.5 + 0.5 * seed.trait_nodes as f64 / TRAIT_NODES).min(MAX_TRAIT_FACTOR)
+    }
+
+    // 컴파일 성공 비율과 mutant 당 성과로 base_energy를 늘리거나 줄인다.
+    // 전부 거부되는 seed는 절반 가까이로, 성과가 많은 seed는 최대 16배까지.
+    pub fn energy(&self, index: usize) -> usize {
+        let seed = &self.seeds[index];
+        let factor = if seed.executions == 0 {
+            self.trait_factor(seed)
+        } else {
+            let executions = seed.executions as f64;
+            let accepted = seed.accepted as f64 / executions;
+            let found = seed.score() as f64 / executions;
+            (0.5 + accepted) * (1.0 + 4.0 * found) * self.trait_factor(seed)
+        };
+        let factor = factor.clamp(MIN_FACTOR, MAX_FACTOR);
+        ((self.base_energy as f64 * factor).round() as usize).max(1)
+    }
+
+    pub fn record(&mut self, index: usize, feedback: &Feedback) {
+        let seed = &mut self.seeds[index];
+        seed.executions += 1;
+        if feedback.accepted {
+            seed.accepted += 1;
+        }
+        seed.new_buckets += feedback.new_buckets;
+        seed.new_diagnostics += feedback.new_diagnostics;
+        seed.new_pairs += feedback.new_pairs;
+    }
+
+    // index seed에서 나온 mutant를 새 seed로 추가한다. 이미 본 내용이면 넣지 않는다
+    pub fn add(&mut self, index: usize, source_code: String) {
+        if !self.hashes.insert(content_hash(&source_code)) {
+            return;
+        }
+        let name = format!("q{}.rs", self.next_id);
+        self.next_id += 1;
+        fs::write(self.queue_dir.join(&name), &source_code).unwrap();
+        let parent = &mut self.seeds[index];
+        parent.children += 1;
+        let parent = Some(parent.name.clone());
+        println!("[queue] {} from {}", name, parent.as_deref().unwrap());
+        self.seeds.push(Seed::new(name, source_code, parent));
+    }
+
+    // queue가 max_seeds 보다 크면 energy가 낮은 추가 seed부터 queue와 queue_dir 에서 지운다.
+    // round 도중에는 index가 바뀌면 안 되니 round가 끝날 때 부른다
+    pub fn evict(&mut self) {
+        let mut added: Vec<usize> = (0..self.seeds.len())
+            .filter(|i| self.seeds[*i].parent.is_some())
+            .collect();
+        let excess = self
+            .seeds
+            .len()
+            .saturating_sub(self.max_seeds)
+            .min(added.len());
+        if excess == 0 {
+            return;
+        }
+        added.sort_by_key(|i| self.energy(*i));
+        let mut evicted: Vec<usize> = added[..excess].to_vec();
+        evicted.sort();
+        for i in evicted.into_iter().rev() {
+            let seed = self.seeds.remove(i);
+            let _ = fs::remove_file(self.queue_dir.join(&seed.name));
+        }
+        self.evicted += excess;
+    }
+
+    pub fn print_summary(&self) {
+        let added = self.seeds.iter().filter(|s| s.parent.is_some()).count();
+        println!(
+            "Seeds: {} ({} added, {} evicted)",
+            self.seeds.len(),
+            added,
+            self.evicted
+        );
+        let mut order: Vec<usize> = (0..self.seeds.len()).collect();
+        order.sort_by_key(|i| {
+            let seed = &self.seeds[*i];
+            std::cmp::Reverse((seed.score(), seed.children))
+        });
+        println!("  seed (parent) : energy, mutants, accepted, buckets, new diagnostics, new pairs, children");
+        for i in order.into_iter().take(20) {
+            let seed = &self.seeds[i];
+            println!(
+                "  {} ({}) : {}, {}, {}, {}, {}, {}, {}",
+                seed.name,
+                seed.parent.as_deref().unwrap_or("-"),
+                self.energy(i),
+                seed.executions,
+                seed.accepted,
+                seed.new_buckets,
+                seed.new_diagnostics,
+                seed.new_pairs,
+                seed.children
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: &str = "fn main() { let x = 1; }";
+    const TRAITS: &str = "trait A {} trait B {} impl A for u8 {} impl B for u8 {}
+fn f<T: A + B>(t: T) where T: Copy {} fn g(x: impl A) -> Box<dyn B> { todo!() }";
+
+    fn queue(sources: &[&str], trait_weighted: bool) -> SeedQueue {
+        let seeds: Vec<Seed> = sources
+            .iter()
+            .enumerate()
+            .map(|(i, s)| Seed::new(format!("s{}.rs", i), s.to_string(), None))
+            .collect();
+        SeedQueue {
+            next_id: seeds.len(),
+            hashes: seeds.iter().map(|s| content_hash(&s.source_code)).collect(),
+            seeds,
+            base_energy: 8,
+            trait_weighted,
+            queue_dir: PathBuf::new(),
+            max_seeds: 1000,
+            evicted: 0,
+        }
+    }
+
+    // add는 queue_dir 에 파일을 쓰니 테스트마다 따로 만든다
+    fn queue_in_temp_dir(name: &str, sources: &[&str], max_seeds: usize) -> SeedQueue {
+        let queue_dir =
+            std::env::temp_dir().join(format!("crate-seeds-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&queue_dir);
+        fs::create_dir_all(&queue_dir).unwrap();
+        let mut queue = queue(sources, false);
+        queue.queue_dir = queue_dir;
+        queue.max_seeds = max_seeds;
+        queue
+    }
+
+    fn feedback(accepted: bool, new_buckets: usize) -> Feedback {
+        Feedback {
+            accepted,
+            new_buckets,
+            new_diagnostics: 0,
+            new_pairs: 0,
+        }
+    }
+
+    #[test]
+    fn unexecuted_seeds_get_base_energy() {
+        let queue = queue(&[PLAIN, TRAITS], false);
+        assert_eq!(queue.energy(0), 8);
+        assert_eq!(queue.energy(1), 8);
+    }
+
+    #[test]
+    fn energy_follows_acceptance_and_findings() {
+        let mut queue = queue(&[PLAIN, PLAIN, PLAIN], false);
+        for _ in 0..4 {
+            queue.record(0, &feedback(false, 0));
+            queue.record(1, &feedback(true, 0));
+        }
+        queue.record(2, &feedback(true, 1));
+        // 0.5배, 1.5배, 그리고 16배에서 잘린다
+        assert_eq!(queue.energy(0), 4);
+        assert_eq!(queue.energy(1), 12);
+        assert_eq!(queue.energy(2), 128);
+    }
+
+    #[test]
+    fn trait_light_seeds_get_less_energy_with_solver() {
+        assert!(solver::trait_weight(TRAITS) >= 8);
+        assert_eq!(solver::trait_weight(PLAIN), 0);
+        let queue = queue(&[PLAIN, TRAITS], true);
+        assert_eq!(queue.energy(0), 4);
+        assert!(queue.energy(1) > 8);
+    }
+
+    #[test]
+    fn add_skips_duplicate_contents() {
+        let mut queue = queue_in_temp_dir("dedup", &[PLAIN], 1000);
+        queue.add(0, PLAIN.to_string());
+        queue.add(0, "fn main() { let y = 2; }".to_string());
+        queue.add(1, "fn main() { let y = 2; }".to_string());
+        assert_eq!(queue.seeds.len(), 2);
+        assert_eq!(queue.seeds[0].children, 1);
+        assert!(queue.queue_dir.join("q1.rs").exists());
+        fs::remove_dir_all(&queue.queue_dir).unwrap();
+    }
+
+    #[test]
+    fn evict_drops_lowest_energy_added_seeds() {
+        let mut queue = queue_in_temp_dir("evict", &[PLAIN, PLAIN], 3);
+        for i in 0..3 {
+            queue.add(0, format!("fn main() {{ let x = {}; }}", i + 10));
+        }
+        // q2 는 한 번도 안 받아들여졌고, q3 는 bucket을 찾았다
+        for _ in 0..4 {
+            queue.record(2, &feedback(true, 0));
+            queue.record(3, &feedback(false, 0));
+            queue.record(4, &feedback(true, 1));
+        }
+        queue.evict();
+        let names: Vec<&str> = queue.seeds.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["s0.rs", "s1.rs", "q4.rs"]);
+        assert!(!queue.queue_dir.join("q3.rs").exists());
+        assert!(queue.queue_dir.join("q4.rs").exists());
+        assert_eq!(queue.evicted, 2);
+
+        // 버린 seed와 같은 내용은 다시 들어오지 않고, 새 이름은 겹치지 않는다
+        queue.add(0, "fn main() { let x = 11; }".to_string());
+        queue.add(0, "fn main() { let x = 20; }".to_string());
+        assert_eq!(queue.seeds.last().unwrap().name, "q5.rs");
+        fs::remove_dir_all(&queue.queue_dir).unwrap();
+    }
+
+    #[test]
+    fn evict_keeps_input_seeds() {
+        let mut queue = queue_in_temp_dir("inputs", &[PLAIN, TRAITS], 1);
+        queue.add(0, "fn main() { let x = 3; }".to_string());
+        queue.evict();
+        assert_eq!(queue.seeds.len(), 2);
+        assert!(queue.seeds.iter().all(|s| s.parent.is_none()));
+        fs::remove_dir_all(&queue.queue_dir).unwrap();
+    }
+}